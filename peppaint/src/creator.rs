@@ -0,0 +1,588 @@
+// Generators for indexed 3D primitives. Every shape is centered at the origin,
+// has outward facing normals and counter clockwise front faces.
+
+use crate::{
+    Mesh,
+    Vertex,
+    Vec2,
+    Vec3,
+    Vec4,
+    std::PosNormTexTan,
+};
+
+use nalgebra_glm as glm;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::f32::consts::{ PI, FRAC_PI_2 as HALF_PI };
+
+// full precision, so the seams of revolved shapes close exactly
+const TWO_PI: f32 = 2.0 * PI;
+
+// triangles with a smaller (doubled) area are left out, e.g. at the poles of a sphere
+const DEGENERATE_AREA: f32 = 1e-10;
+
+pub struct Geometry {
+    pub vertices: Vec<PosNormTexTan>,
+    pub indices: Vec<u32>,
+}
+
+impl Geometry {
+
+    pub fn new() -> Self {
+        Self { vertices: Vec::new(), indices: Vec::new() }
+    }
+
+    pub fn into_mesh(self) -> Mesh<PosNormTexTan> {
+        Mesh::with_content(self.vertices, self.indices)
+    }
+
+    // for meshes with another vertex type, e.g. |v| PosColor { pos: v.pos, color }
+    pub fn into_mesh_with<T, F>(self, f: F) -> Mesh<T>
+        where T: Vertex + Copy + Debug, F: Fn(&PosNormTexTan) -> T
+    {
+        let vertices = self.vertices.iter().map(f).collect();
+        Mesh::with_content(vertices, self.indices)
+    }
+
+    pub fn n_triangles(&self) -> usize { self.indices.len() / 3 }
+
+    // appends another geometry, e.g. to build a compound shape in one draw call
+    pub fn merge(&mut self, other: &Geometry) {
+        let base = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map(|i| i + base));
+    }
+
+    pub fn transform(&mut self, m: &glm::Mat4) {
+        let normal_mat = glm::transpose(&glm::inverse(&glm::mat4_to_mat3(m)));
+        let tangent_mat = glm::mat4_to_mat3(m);
+        for v in self.vertices.iter_mut() {
+            let p = m * glm::vec4(v.pos.0, v.pos.1, v.pos.2, 1.0);
+            let n = glm::normalize(&(normal_mat * glm::vec3(v.normal.0, v.normal.1, v.normal.2)));
+            let t = glm::normalize(&(tangent_mat * glm::vec3(v.tangent.0, v.tangent.1, v.tangent.2)));
+            v.pos = Vec3(p.x, p.y, p.z);
+            v.normal = Vec3(n.x, n.y, n.z);
+            v.tangent = Vec4(t.x, t.y, t.z, v.tangent.3);
+        }
+        // mirroring flips the winding
+        if glm::determinant(m) < 0.0 {
+            for tri in self.indices.chunks_mut(3) { tri.swap(1, 2); }
+        }
+    }
+
+    fn push(&mut self, pos: glm::Vec3, normal: glm::Vec3, uv: glm::Vec2) -> u32 {
+        self.vertices.push(PosNormTexTan {
+            pos: Vec3(pos.x, pos.y, pos.z),
+            normal: Vec3(normal.x, normal.y, normal.z),
+            texcoords: Vec2(uv.x, uv.y),
+            tangent: Vec4(0.0, 0.0, 0.0, 1.0),
+        });
+        (self.vertices.len() - 1) as u32
+    }
+
+    fn position(&self, i: u32) -> glm::Vec3 {
+        let p = self.vertices[i as usize].pos;
+        glm::vec3(p.0, p.1, p.2)
+    }
+
+    fn push_tri(&mut self, a: u32, b: u32, c: u32) {
+        let (pa, pb, pc) = (self.position(a), self.position(b), self.position(c));
+        if glm::length2(&glm::cross(&(pb - pa), &(pc - pa))) < DEGENERATE_AREA {
+            return
+        }
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    // (nu + 1) * (nv + 1) vertices, the surface normal is d/du x d/dv
+    fn grid<F>(&mut self, nu: usize, nv: usize, f: F)
+        where F: Fn(f32, f32) -> (glm::Vec3, glm::Vec3)
+    {
+        let base = self.vertices.len() as u32;
+        for j in 0..=nv {
+            for i in 0..=nu {
+                let u = i as f32 / nu as f32;
+                let v = j as f32 / nv as f32;
+                let (pos, normal) = f(u, v);
+                self.push(pos, normal, glm::vec2(u, v));
+            }
+        }
+        let row = (nu + 1) as u32;
+        for j in 0..nv as u32 {
+            for i in 0..nu as u32 {
+                let a = base + j * row + i;
+                let b = a + 1;
+                let d = a + row;
+                let c = d + 1;
+                self.push_tri(a, b, c);
+                self.push_tri(a, c, d);
+            }
+        }
+    }
+
+    // revolves a profile, given from bottom to top, around the y axis
+    fn lathe(&mut self, profile: &[ProfilePoint], sectors: usize) {
+        let n = profile.len() - 1;
+        self.grid(sectors, n, |u, v| {
+            let p = &profile[(v * n as f32).round() as usize];
+            let (s, c) = (u * TWO_PI).sin_cos();
+            let pos = glm::vec3(p.radius * s, p.y, p.radius * c);
+            let normal = glm::vec3(p.normal.x * s, p.normal.y, p.normal.x * c);
+            (pos, normal)
+        });
+        // the profile may not be evenly spaced, so set v afterwards
+        let count = (sectors + 1) * profile.len();
+        let start = self.vertices.len() - count;
+        for (k, vertex) in self.vertices[start..].iter_mut().enumerate() {
+            vertex.texcoords.1 = profile[k / (sectors + 1)].v;
+        }
+    }
+
+    fn disk(&mut self, y: f32, radius: f32, sectors: usize, up: bool) {
+        if radius <= 0.0 { return }
+        let normal = glm::vec3(0.0, if up { 1.0 } else { -1.0 }, 0.0);
+        let center = self.push(glm::vec3(0.0, y, 0.0), normal, glm::vec2(0.5, 0.5));
+        for i in 0..=sectors {
+            let (s, c) = (i as f32 / sectors as f32 * TWO_PI).sin_cos();
+            let uv = glm::vec2(0.5 + 0.5 * s, 0.5 + if up { -0.5 } else { 0.5 } * c);
+            self.push(glm::vec3(radius * s, y, radius * c), normal, uv);
+        }
+        for i in 0..sectors as u32 {
+            let (a, b) = (center + 1 + i, center + 2 + i);
+            if up { self.push_tri(center, a, b) } else { self.push_tri(center, b, a) }
+        }
+    }
+
+    // per vertex tangents from the texture coordinates, orthogonalized against the normal
+    pub fn compute_tangents(&mut self) {
+        let n = self.vertices.len();
+        let mut tangents = vec![glm::vec3(0.0, 0.0, 0.0); n];
+        let mut bitangents = vec![glm::vec3(0.0, 0.0, 0.0); n];
+
+        for tri in self.indices.chunks(3) {
+            let (a, b, c) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+            let (pa, pb, pc) = (self.position(tri[0]), self.position(tri[1]), self.position(tri[2]));
+            let uv = |i: usize| {
+                let t = self.vertices[i].texcoords;
+                glm::vec2(t.0, t.1)
+            };
+            let (e1, e2) = (pb - pa, pc - pa);
+            let (d1, d2) = (uv(b) - uv(a), uv(c) - uv(a));
+            let det = d1.x * d2.y - d2.x * d1.y;
+            if det.abs() < 1e-12 { continue }
+            let r = 1.0 / det;
+            let t = (e1 * d2.y - e2 * d1.y) * r;
+            let bt = (e2 * d1.x - e1 * d2.x) * r;
+            for i in [a, b, c].iter() {
+                tangents[*i] += t;
+                bitangents[*i] += bt;
+            }
+        }
+
+        for (i, v) in self.vertices.iter_mut().enumerate() {
+            let normal = glm::vec3(v.normal.0, v.normal.1, v.normal.2);
+            let mut t = tangents[i] - normal * glm::dot(&normal, &tangents[i]);
+            if glm::length2(&t) < 1e-12 {
+                // no usable uv gradient (e.g. a pole), any perpendicular will do
+                let other = if normal.x.abs() < 0.9 { glm::Vec3::x() } else { glm::Vec3::y() };
+                t = glm::cross(&other, &normal);
+            }
+            let t = glm::normalize(&t);
+            let w = if glm::dot(&glm::cross(&normal, &t), &bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+            v.tangent = Vec4(t.x, t.y, t.z, w);
+        }
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self { Self::new() }
+}
+
+struct ProfilePoint {
+    radius: f32,
+    y: f32,
+    // (radial, y) components of the normal
+    normal: glm::Vec2,
+    v: f32,
+}
+
+// SHAPES
+
+pub fn plane(width: f32, depth: f32, x_segments: usize, z_segments: usize) -> Geometry {
+    let mut g = Geometry::new();
+    g.grid(x_segments.max(1), z_segments.max(1), |u, v| {
+        let pos = glm::vec3((u - 0.5) * width, 0.0, (0.5 - v) * depth);
+        (pos, glm::Vec3::y())
+    });
+    g.compute_tangents();
+    g
+}
+
+pub fn cube(size: f32) -> Geometry {
+    cuboid(size, size, size, 1)
+}
+
+pub fn cuboid(width: f32, height: f32, depth: f32, segments: usize) -> Geometry {
+    let half = glm::vec3(width / 2.0, height / 2.0, depth / 2.0);
+    let (x, y, z) = (glm::Vec3::x(), glm::Vec3::y(), glm::Vec3::z());
+    // (u axis, v axis) for every face, the normal is u x v
+    let faces = [
+        (-z, y), (z, y),
+        (x, -z), (x, z),
+        (x, y), (-x, y),
+    ];
+    let segments = segments.max(1);
+    let mut g = Geometry::new();
+    for (u_axis, v_axis) in faces.iter() {
+        let normal = glm::cross(u_axis, v_axis);
+        g.grid(segments, segments, |u, v| {
+            let p = normal + u_axis * (2.0 * u - 1.0) + v_axis * (2.0 * v - 1.0);
+            (p.component_mul(&half), normal)
+        });
+    }
+    g.compute_tangents();
+    g
+}
+
+pub fn uv_sphere(radius: f32, sectors: usize, stacks: usize) -> Geometry {
+    let stacks = stacks.max(2);
+    let profile: Vec<ProfilePoint> = (0..=stacks).map(|j| {
+        let v = j as f32 / stacks as f32;
+        let (s, c) = (v * PI - HALF_PI).sin_cos();
+        ProfilePoint { radius: radius * c, y: radius * s, normal: glm::vec2(c, s), v }
+    }).collect();
+    let mut g = Geometry::new();
+    g.lathe(&profile, sectors.max(3));
+    g.compute_tangents();
+    g
+}
+
+pub fn icosphere(radius: f32, subdivisions: usize) -> Geometry {
+    let t = (1.0 + 5f32.sqrt()) / 2.0;
+    let mut points: Vec<glm::Vec3> = [
+        (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+        (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+        (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+    ].iter().map(|p| glm::normalize(&glm::vec3(p.0, p.1, p.2))).collect();
+    let mut faces: Vec<[usize; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, points: &mut Vec<glm::Vec3>| {
+            let key = (a.min(b), a.max(b));
+            *midpoints.entry(key).or_insert_with(|| {
+                points.push(glm::normalize(&(points[a] + points[b])));
+                points.len() - 1
+            })
+        };
+        let mut next = Vec::with_capacity(faces.len() * 4);
+        for f in faces.iter() {
+            let ab = midpoint(f[0], f[1], &mut points);
+            let bc = midpoint(f[1], f[2], &mut points);
+            let ca = midpoint(f[2], f[0], &mut points);
+            next.push([f[0], ab, ca]);
+            next.push([f[1], bc, ab]);
+            next.push([f[2], ca, bc]);
+            next.push([ab, bc, ca]);
+        }
+        faces = next;
+    }
+
+    // same mapping as the uv sphere, triangles crossing the seam get their own vertices
+    let uv = |p: &glm::Vec3| glm::vec2(0.5 + p.x.atan2(p.z) / TWO_PI, 0.5 + p.y.asin() / PI);
+    let mut g = Geometry::new();
+    for p in points.iter() {
+        g.push(p * radius, *p, uv(p));
+    }
+    let mut seam: HashMap<usize, u32> = HashMap::new();
+    for f in faces.iter() {
+        let us: Vec<f32> = f.iter().map(|i| uv(&points[*i]).x).collect();
+        let wraps = us.iter().cloned().fold(0.0, f32::max) - us.iter().cloned().fold(1.0, f32::min) > 0.5;
+        let mut tri = [f[0] as u32, f[1] as u32, f[2] as u32];
+        if wraps {
+            for k in 0..3 {
+                if us[k] < 0.5 {
+                    let p = points[f[k]];
+                    let uv = uv(&p) + glm::vec2(1.0, 0.0);
+                    tri[k] = *seam.entry(f[k]).or_insert_with(|| g.push(p * radius, p, uv));
+                }
+            }
+        }
+        g.indices.extend_from_slice(&tri);
+    }
+    g.compute_tangents();
+    g
+}
+
+pub fn cylinder(radius: f32, height: f32, sectors: usize, stacks: usize, caps: bool) -> Geometry {
+    frustum(radius, radius, height, sectors, stacks, caps)
+}
+
+pub fn cone(radius: f32, height: f32, sectors: usize, stacks: usize, cap: bool) -> Geometry {
+    frustum(radius, 0.0, height, sectors, stacks, cap)
+}
+
+// a cylinder with different radii at the bottom and the top
+pub fn frustum(
+    bottom_radius: f32,
+    top_radius: f32,
+    height: f32,
+    sectors: usize,
+    stacks: usize,
+    caps: bool
+) -> Geometry {
+    let stacks = stacks.max(1);
+    let sectors = sectors.max(3);
+    let slope = glm::normalize(&glm::vec2(height, bottom_radius - top_radius));
+    let profile: Vec<ProfilePoint> = (0..=stacks).map(|j| {
+        let v = j as f32 / stacks as f32;
+        ProfilePoint {
+            radius: bottom_radius + (top_radius - bottom_radius) * v,
+            y: (v - 0.5) * height,
+            normal: slope,
+            v
+        }
+    }).collect();
+    let mut g = Geometry::new();
+    g.lathe(&profile, sectors);
+    if caps {
+        g.disk(-height / 2.0, bottom_radius, sectors, false);
+        g.disk(height / 2.0, top_radius, sectors, true);
+    }
+    g.compute_tangents();
+    g
+}
+
+pub fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: usize,
+    minor_segments: usize
+) -> Geometry {
+    let minor_segments = minor_segments.max(3);
+    let profile: Vec<ProfilePoint> = (0..=minor_segments).map(|j| {
+        let v = j as f32 / minor_segments as f32;
+        let (s, c) = (v * TWO_PI).sin_cos();
+        ProfilePoint {
+            radius: major_radius + minor_radius * c,
+            y: minor_radius * s,
+            normal: glm::vec2(c, s),
+            v
+        }
+    }).collect();
+    let mut g = Geometry::new();
+    g.lathe(&profile, major_segments.max(3));
+    g.compute_tangents();
+    g
+}
+
+// height is the length of the cylindrical part, the total height is height + 2 * radius
+pub fn capsule(radius: f32, height: f32, sectors: usize, hemisphere_stacks: usize) -> Geometry {
+    let stacks = hemisphere_stacks.max(1);
+    let total = height + 2.0 * radius;
+    let mut profile = Vec::with_capacity(2 * stacks + 2);
+    for (center, from) in [(-height / 2.0, -HALF_PI), (height / 2.0, 0.0)].iter() {
+        for j in 0..=stacks {
+            let (s, c) = (from + HALF_PI * j as f32 / stacks as f32).sin_cos();
+            let y = center + radius * s;
+            profile.push(ProfilePoint {
+                radius: radius * c,
+                y,
+                normal: glm::vec2(c, s),
+                v: (y + total / 2.0) / total
+            });
+        }
+    }
+    let mut g = Geometry::new();
+    g.lathe(&profile, sectors.max(3));
+    g.compute_tangents();
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Key = (i64, i64, i64);
+
+    // seams duplicate vertices, so edges are matched by position
+    fn key(v: &PosNormTexTan) -> Key {
+        let q = |f: f32| (f * 1e4).round() as i64;
+        (q(v.pos.0), q(v.pos.1), q(v.pos.2))
+    }
+
+    fn vec(v: &Vec3) -> glm::Vec3 {
+        glm::vec3(v.0, v.1, v.2)
+    }
+
+    // how many triangles use every edge
+    fn edge_uses(g: &Geometry) -> HashMap<(Key, Key), usize> {
+        let mut uses = HashMap::new();
+        for tri in g.indices.chunks(3) {
+            for k in 0..3 {
+                let a = key(&g.vertices[tri[k] as usize]);
+                let b = key(&g.vertices[tri[(k + 1) % 3] as usize]);
+                assert_ne!(a, b, "degenerate edge");
+                let edge = if a < b { (a, b) } else { (b, a) };
+                *uses.entry(edge).or_insert(0) += 1;
+            }
+        }
+        uses
+    }
+
+    fn assert_closed(g: &Geometry) {
+        for (edge, n) in edge_uses(g) {
+            assert_eq!(n, 2, "edge {:?} has {} faces", edge, n);
+        }
+    }
+
+    // the normals point away from center(position), and the winding agrees with them
+    fn assert_outward<F: Fn(&glm::Vec3) -> glm::Vec3>(g: &Geometry, center: F) {
+        for v in g.vertices.iter() {
+            let (p, n) = (vec(&v.pos), vec(&v.normal));
+            assert!((glm::length(&n) - 1.0).abs() < 1e-4, "normal {:?} is not unit length", n);
+            assert!(glm::dot(&n, &(p - center(&p))) > 0.0, "normal {:?} at {:?} points inward", n, p);
+        }
+        assert_winding(g);
+    }
+
+    fn assert_winding(g: &Geometry) {
+        for tri in g.indices.chunks(3) {
+            let v: Vec<&PosNormTexTan> = tri.iter().map(|i| &g.vertices[*i as usize]).collect();
+            let face = glm::cross(&(vec(&v[1].pos) - vec(&v[0].pos)), &(vec(&v[2].pos) - vec(&v[0].pos)));
+            let normal = vec(&v[0].normal) + vec(&v[1].normal) + vec(&v[2].normal);
+            assert!(glm::dot(&face, &normal) > 0.0, "triangle {:?} is wound against its normals", tri);
+        }
+    }
+
+    fn origin(_: &glm::Vec3) -> glm::Vec3 {
+        glm::vec3(0.0, 0.0, 0.0)
+    }
+
+    fn n_positions(g: &Geometry) -> usize {
+        g.vertices.iter().map(key).collect::<std::collections::HashSet<_>>().len()
+    }
+
+    #[test]
+    fn plane_counts_and_normals() {
+        let (nx, nz) = (3, 5);
+        let g = plane(2.0, 4.0, nx, nz);
+        assert_eq!(g.vertices.len(), (nx + 1) * (nz + 1));
+        assert_eq!(g.n_triangles(), 2 * nx * nz);
+        assert!(g.vertices.iter().all(|v| vec(&v.normal) == glm::Vec3::y()));
+        assert_winding(&g);
+        // open, the border edges have one face
+        let border = edge_uses(&g).values().filter(|n| **n == 1).count();
+        assert_eq!(border, 2 * (nx + nz));
+        assert!(edge_uses(&g).values().all(|n| *n <= 2));
+    }
+
+    #[test]
+    fn cube_and_cuboid() {
+        let g = cube(1.0);
+        assert_eq!(g.vertices.len(), 6 * 4);
+        assert_eq!(g.n_triangles(), 6 * 2);
+        assert_eq!(n_positions(&g), 8);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+
+        let s = 3;
+        let g = cuboid(1.0, 2.0, 3.0, s);
+        assert_eq!(g.vertices.len(), 6 * (s + 1) * (s + 1));
+        assert_eq!(g.n_triangles(), 6 * 2 * s * s);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+    }
+
+    #[test]
+    fn uv_sphere_closed() {
+        let (sectors, stacks) = (16, 8);
+        let g = uv_sphere(1.5, sectors, stacks);
+        assert_eq!(g.vertices.len(), (sectors + 1) * (stacks + 1));
+        // one triangle per sector collapses at each pole
+        assert_eq!(g.n_triangles(), 2 * sectors * stacks - 2 * sectors);
+        assert_eq!(n_positions(&g), sectors * (stacks - 1) + 2);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+    }
+
+    #[test]
+    fn icosphere_closed() {
+        for n in 0..3 {
+            let g = icosphere(1.0, n);
+            let faces = 20 * 4usize.pow(n as u32);
+            assert_eq!(g.n_triangles(), faces);
+            // euler: v - e + f = 2 with e = 3f / 2, the rest are seam copies
+            assert_eq!(n_positions(&g), faces / 2 + 2);
+            assert!(g.vertices.len() >= faces / 2 + 2);
+            assert_closed(&g);
+            assert_outward(&g, origin);
+        }
+    }
+
+    #[test]
+    fn cylinder_with_and_without_caps() {
+        let (sectors, stacks) = (12, 3);
+        let g = cylinder(1.0, 2.0, sectors, stacks, true);
+        assert_eq!(g.vertices.len(), (sectors + 1) * (stacks + 1) + 2 * (sectors + 2));
+        assert_eq!(g.n_triangles(), 2 * sectors * stacks + 2 * sectors);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+
+        let g = cylinder(1.0, 2.0, sectors, stacks, false);
+        assert_eq!(g.vertices.len(), (sectors + 1) * (stacks + 1));
+        assert_eq!(g.n_triangles(), 2 * sectors * stacks);
+        let border = edge_uses(&g).values().filter(|n| **n == 1).count();
+        assert_eq!(border, 2 * sectors);
+        assert_outward(&g, origin);
+    }
+
+    #[test]
+    fn cone_closed() {
+        let (sectors, stacks) = (10, 4);
+        let g = cone(1.0, 2.0, sectors, stacks, true);
+        // no top cap, and the triangles at the tip that collapse are left out
+        assert_eq!(g.vertices.len(), (sectors + 1) * (stacks + 1) + sectors + 2);
+        assert_eq!(g.n_triangles(), 2 * sectors * stacks - sectors + sectors);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+    }
+
+    #[test]
+    fn frustum_closed() {
+        let (sectors, stacks) = (8, 2);
+        let g = frustum(1.0, 0.5, 1.0, sectors, stacks, true);
+        assert_eq!(g.vertices.len(), (sectors + 1) * (stacks + 1) + 2 * (sectors + 2));
+        assert_eq!(g.n_triangles(), 2 * sectors * stacks + 2 * sectors);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+    }
+
+    #[test]
+    fn torus_closed() {
+        let (major, minor) = (3.0, 1.0);
+        let (nu, nv) = (24, 12);
+        let g = torus(major, minor, nu, nv);
+        assert_eq!(g.vertices.len(), (nu + 1) * (nv + 1));
+        assert_eq!(g.n_triangles(), 2 * nu * nv);
+        assert_eq!(n_positions(&g), nu * nv);
+        assert_closed(&g);
+        // outward from the middle of the tube
+        assert_outward(&g, |p| glm::normalize(&glm::vec3(p.x, 0.0, p.z)) * major);
+    }
+
+    #[test]
+    fn capsule_closed() {
+        let (sectors, stacks) = (12, 4);
+        let g = capsule(0.5, 1.0, sectors, stacks);
+        let rings = 2 * stacks + 2;
+        assert_eq!(g.vertices.len(), (sectors + 1) * rings);
+        assert_eq!(g.n_triangles(), 2 * sectors * (rings - 1) - 2 * sectors);
+        assert_closed(&g);
+        assert_outward(&g, origin);
+    }
+}
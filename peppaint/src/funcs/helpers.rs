@@ -1,5 +1,7 @@
-use rand::random;
+use rand::Rng;
+use super::random::with_rng;
 use std::mem::MaybeUninit;
+use std::time::{ Duration, Instant };
 
 pub fn uninit<T>() -> T {
     unsafe { MaybeUninit::<T>::uninit().assume_init() }
@@ -16,15 +18,102 @@ pub fn map(
 }
 
 pub fn randomf(min: f32, max: f32) -> f32 {
-    let mut r = random::<f32>();
+    let mut r = with_rng(|rng| rng.gen::<f32>());
     r *= max - min;
     r += min;
     r
 }
 
 pub fn randomuint(min: usize, max: usize) -> usize {
-    let mut r = random::<f32>();
+    let mut r = with_rng(|rng| rng.gen::<f32>());
     r *= (max as f32) - (min as f32);
     r += min as f32;
     r as usize
-}
\ No newline at end of file
+}
+
+// the value of "--name <value>" or "--name=<value>" on the command line
+pub fn arg_value(name: &str) -> Option<String> {
+    let flag = format!("--{}", name);
+    let prefix = format!("--{}=", name);
+    let args: Vec<String> = std::env::args().collect();
+    for (i, arg) in args.iter().enumerate() {
+        if *arg == flag {
+            return args.get(i + 1).cloned()
+        }
+        if arg.starts_with(&prefix) {
+            return Some(arg[prefix.len()..].to_string())
+        }
+    }
+    None
+}
+
+// TIME
+
+// thread::sleep can overshoot by a few milliseconds, so the last part is spun away
+pub fn sleep_until(deadline: Instant) {
+    const SPIN: Duration = Duration::from_millis(2);
+    let now = Instant::now();
+    if deadline <= now { return }
+    if deadline - now > SPIN {
+        std::thread::sleep(deadline - now - SPIN);
+    }
+    while Instant::now() < deadline {
+        std::thread::yield_now();
+    }
+}
+
+// VERTEX PACKING
+
+// converts to IEEE 754 half precision, to be used with Type::HalfFloat
+pub fn to_half(f: f32) -> u16 {
+    let bits = f.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x007f_ffff;
+
+    if exp == 0xff {
+        // inf or nan
+        let nan = if mant != 0 { 0x0200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exp = exp - 127 + 15;
+    if exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exp <= 0 {
+        if exp < -10 { return sign }
+        let mant = mant | 0x0080_0000;
+        let shift = (14 - exp) as u32;
+        let half_mant = mant >> shift;
+        let round = (mant >> (shift - 1)) & 1;
+        return sign | (half_mant + round) as u16;
+    }
+
+    let half = sign | ((exp as u16) << 10) | (mant >> 13) as u16;
+    // round to nearest, a carry into the exponent is still correct
+    half + ((mant >> 12) & 1) as u16
+}
+
+// packs a signed normalized vector (e.g. a normal), to be used with
+// Type::Int2_10_10_10Rev and normalized: true
+pub fn pack_snorm_2_10_10_10(x: f32, y: f32, z: f32, w: f32) -> u32 {
+    let snorm = |v: f32, max: f32, bits: u32| {
+        let v = (v.clamp(-1.0, 1.0) * max).round() as i32;
+        (v as u32) & ((1 << bits) - 1)
+    };
+    snorm(x, 511.0, 10)
+        | (snorm(y, 511.0, 10) << 10)
+        | (snorm(z, 511.0, 10) << 20)
+        | (snorm(w, 1.0, 2) << 30)
+}
+
+// packs an unsigned normalized vector (e.g. a color), to be used with
+// Type::Uint2_10_10_10Rev and normalized: true
+pub fn pack_unorm_2_10_10_10(x: f32, y: f32, z: f32, w: f32) -> u32 {
+    let unorm = |v: f32, max: f32| (v.clamp(0.0, 1.0) * max).round() as u32;
+    unorm(x, 1023.0)
+        | (unorm(y, 1023.0) << 10)
+        | (unorm(z, 1023.0) << 20)
+        | (unorm(w, 3.0) << 30)
+}
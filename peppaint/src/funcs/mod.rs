@@ -1,2 +1,6 @@
 pub mod helpers;
-pub use helpers::*;
\ No newline at end of file
+pub use helpers::*;
+pub mod random;
+pub use random::*;
+pub mod noise;
+pub use noise::*;
@@ -0,0 +1,338 @@
+use rand::{ SeedableRng, rngs::StdRng, seq::SliceRandom };
+
+// all noise is roughly in [-1, 1] and the same for the same seed
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoiseKind {
+    Value,
+    Perlin,
+    Simplex,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Fbm {
+    pub octaves: usize,
+    // frequency multiplier per octave
+    pub lacunarity: f32,
+    // amplitude multiplier per octave
+    pub gain: f32,
+}
+
+impl Fbm {
+    pub fn new(octaves: usize) -> Self {
+        Self { octaves, lacunarity: 2.0, gain: 0.5 }
+    }
+}
+
+impl Default for Fbm {
+    fn default() -> Self { Self::new(5) }
+}
+
+#[derive(Clone)]
+pub struct Noise {
+    seed: u64,
+    // a shuffled 0..256 written twice, so perm[a + b] never runs out
+    perm: [u8; 512],
+}
+
+impl Noise {
+
+    pub fn new(seed: u64) -> Self {
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(&mut StdRng::seed_from_u64(seed));
+        let mut perm = [0u8; 512];
+        for i in 0..512 {
+            perm[i] = table[i & 255];
+        }
+        Self { seed, perm }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    // VALUE
+
+    pub fn value1(&self, x: f32) -> f32 { self.lattice(&[x], false) }
+    pub fn value2(&self, x: f32, y: f32) -> f32 { self.lattice(&[x, y], false) }
+    pub fn value3(&self, x: f32, y: f32, z: f32) -> f32 { self.lattice(&[x, y, z], false) }
+    pub fn value4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 { self.lattice(&[x, y, z, w], false) }
+
+    // PERLIN
+
+    pub fn perlin1(&self, x: f32) -> f32 { self.lattice(&[x], true) * 2.0 }
+    pub fn perlin2(&self, x: f32, y: f32) -> f32 { self.lattice(&[x, y], true) }
+    pub fn perlin3(&self, x: f32, y: f32, z: f32) -> f32 { self.lattice(&[x, y, z], true) }
+    pub fn perlin4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 { self.lattice(&[x, y, z, w], true) }
+
+    // SIMPLEX
+
+    pub fn simplex1(&self, x: f32) -> f32 {
+        let i0 = x.floor() as i32;
+        let x0 = x - i0 as f32;
+        let x1 = x0 - 1.0;
+        let n0 = falloff(1.0 - x0 * x0) * grad1(self.hash(&[i0]), x0);
+        let n1 = falloff(1.0 - x1 * x1) * grad1(self.hash(&[i0 + 1]), x1);
+        3.16 * (n0 + n1)
+    }
+
+    pub fn simplex2(&self, x: f32, y: f32) -> f32 {
+        const F2: f32 = 0.36602542; // (sqrt(3) - 1) / 2
+        const G2: f32 = 0.21132487; // (3 - sqrt(3)) / 6
+
+        // skew into the grid of triangles
+        let s = (x + y) * F2;
+        let i = (x + s).floor() as i32;
+        let j = (y + s).floor() as i32;
+        let t = (i + j) as f32 * G2;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+
+        // upper or lower triangle
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let x1 = x0 - i1 as f32 + G2;
+        let y1 = y0 - j1 as f32 + G2;
+        let x2 = x0 - 1.0 + 2.0 * G2;
+        let y2 = y0 - 1.0 + 2.0 * G2;
+
+        let n0 = falloff(0.5 - x0 * x0 - y0 * y0) * grad2(self.hash(&[i, j]), x0, y0);
+        let n1 = falloff(0.5 - x1 * x1 - y1 * y1) * grad2(self.hash(&[i + i1, j + j1]), x1, y1);
+        let n2 = falloff(0.5 - x2 * x2 - y2 * y2) * grad2(self.hash(&[i + 1, j + 1]), x2, y2);
+        70.0 * (n0 + n1 + n2)
+    }
+
+    pub fn simplex3(&self, x: f32, y: f32, z: f32) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+        self.simplex(&[x, y, z], F3, G3, 32.0)
+    }
+
+    pub fn simplex4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        const F4: f32 = 0.309017; // (sqrt(5) - 1) / 4
+        const G4: f32 = 0.1381966; // (5 - sqrt(5)) / 20
+        self.simplex(&[x, y, z, w], F4, G4, 27.0)
+    }
+
+    // ANY
+
+    // p has 1 to 4 coordinates
+    pub fn sample(&self, kind: NoiseKind, p: &[f32]) -> f32 {
+        match (kind, p.len()) {
+            (NoiseKind::Value, 1..=4) => self.lattice(p, false),
+            (NoiseKind::Perlin, 1) => self.perlin1(p[0]),
+            (NoiseKind::Perlin, 2..=4) => self.lattice(p, true),
+            (NoiseKind::Simplex, 1) => self.simplex1(p[0]),
+            (NoiseKind::Simplex, 2) => self.simplex2(p[0], p[1]),
+            (NoiseKind::Simplex, 3) => self.simplex3(p[0], p[1], p[2]),
+            (NoiseKind::Simplex, 4) => self.simplex4(p[0], p[1], p[2], p[3]),
+            _ => {
+                println!("Noise only goes from 1 to 4 dimensions, got {}", p.len());
+                0.0
+            }
+        }
+    }
+
+    // octaves of noise added together, still roughly in [-1, 1]
+    pub fn fbm(&self, kind: NoiseKind, p: &[f32], fbm: &Fbm) -> f32 {
+        let dims = p.len().min(4);
+        let mut q = [0f32; 4];
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        for octave in 0..fbm.octaves {
+            // shifted a bit per octave, so the octaves don't all line up at the origin
+            let shift = octave as f32 * 17.31;
+            for d in 0..dims {
+                q[d] = p[d] * frequency + shift;
+            }
+            sum += amplitude * self.sample(kind, &q[..dims]);
+            total += amplitude;
+            amplitude *= fbm.gain;
+            frequency *= fbm.lacunarity;
+        }
+        if total > 0.0 { sum / total } else { 0.0 }
+    }
+
+    // p pushed around by fbm before it is sampled
+    pub fn warp(&self, kind: NoiseKind, p: &[f32], strength: f32, fbm: &Fbm) -> [f32; 4] {
+        const OFFSETS: [[f32; 4]; 4] = [
+            [0.0, 0.0, 0.0, 0.0],
+            [5.2, 1.3, 2.8, 7.1],
+            [9.7, 4.4, 6.1, 3.3],
+            [2.6, 8.9, 1.7, 5.5],
+        ];
+        let dims = p.len().min(4);
+        let mut q = [0f32; 4];
+        let mut warped = [0f32; 4];
+        for d in 0..dims {
+            for e in 0..dims {
+                q[e] = p[e] + OFFSETS[d][e];
+            }
+            warped[d] = p[d] + strength * self.fbm(kind, &q[..dims], fbm);
+        }
+        warped
+    }
+
+    // fbm of the warped p
+    pub fn domain_warp(&self, kind: NoiseKind, p: &[f32], strength: f32, fbm: &Fbm) -> f32 {
+        let dims = p.len().min(4);
+        let warped = self.warp(kind, p, strength, fbm);
+        self.fbm(kind, &warped[..dims], fbm)
+    }
+
+    pub fn domain_warp2(&self, x: f32, y: f32, strength: f32) -> f32 {
+        self.domain_warp(NoiseKind::Simplex, &[x, y], strength, &Fbm::default())
+    }
+
+    pub fn domain_warp3(&self, x: f32, y: f32, z: f32, strength: f32) -> f32 {
+        self.domain_warp(NoiseKind::Simplex, &[x, y, z], strength, &Fbm::default())
+    }
+
+    // INNER
+
+    fn hash(&self, cell: &[i32]) -> usize {
+        let mut h = 0usize;
+        for c in cell {
+            h = self.perm[h + (c & 255) as usize] as usize;
+        }
+        h
+    }
+
+    // value or perlin noise, by blending the 2^n corners of the cell around p
+    fn lattice(&self, p: &[f32], gradient: bool) -> f32 {
+        let dims = p.len();
+        let mut cell = [0i32; 4];
+        let mut frac = [0f32; 4];
+        let mut fade = [0f32; 4];
+        for d in 0..dims {
+            let floor = p[d].floor();
+            cell[d] = floor as i32;
+            frac[d] = p[d] - floor;
+            fade[d] = smootherstep(frac[d]);
+        }
+
+        let corners = 1 << dims;
+        let mut values = [0f32; 16];
+        let mut corner = [0i32; 4];
+        let mut offset = [0f32; 4];
+        for (c, value) in values.iter_mut().enumerate().take(corners) {
+            for d in 0..dims {
+                let o = ((c >> d) & 1) as i32;
+                corner[d] = cell[d] + o;
+                offset[d] = frac[d] - o as f32;
+            }
+            let h = self.hash(&corner[..dims]);
+            *value = if !gradient {
+                h as f32 / 255.0 * 2.0 - 1.0
+            } else {
+                match dims {
+                    1 => grad1(h, offset[0]),
+                    2 => grad2(h, offset[0], offset[1]),
+                    3 => grad3(h, offset[0], offset[1], offset[2]),
+                    _ => grad4(h, offset[0], offset[1], offset[2], offset[3]),
+                }
+            };
+        }
+
+        // blend away one axis at a time, x first
+        let mut count = corners;
+        for t in fade.iter().take(dims) {
+            count /= 2;
+            for k in 0..count {
+                values[k] = lerp(values[2 * k], values[2 * k + 1], *t);
+            }
+        }
+        values[0]
+    }
+
+    // 3 or 4 dimensional simplex noise
+    fn simplex(&self, p: &[f32], skew: f32, unskew: f32, scale: f32) -> f32 {
+        let dims = p.len();
+
+        // skew into the grid of simplices
+        let s = p.iter().sum::<f32>() * skew;
+        let mut cell = [0i32; 4];
+        for d in 0..dims {
+            cell[d] = (p[d] + s).floor() as i32;
+        }
+        let t = cell[..dims].iter().sum::<i32>() as f32 * unskew;
+        let mut x0 = [0f32; 4];
+        for d in 0..dims {
+            x0[d] = p[d] - (cell[d] as f32 - t);
+        }
+
+        // the simplex is found by walking the axes from the largest offset to the smallest
+        let mut rank = [0usize; 4];
+        for a in 0..dims {
+            for b in a + 1..dims {
+                if x0[a] > x0[b] { rank[a] += 1 } else { rank[b] += 1 }
+            }
+        }
+
+        let mut sum = 0.0;
+        let mut corner = [0i32; 4];
+        let mut x = [0f32; 4];
+        for k in 0..=dims {
+            for d in 0..dims {
+                // the k'th corner has stepped along the k largest axes
+                let step = if rank[d] + k >= dims { 1 } else { 0 };
+                corner[d] = cell[d] + step;
+                x[d] = x0[d] - step as f32 + k as f32 * unskew;
+            }
+            let r = x[..dims].iter().map(|v| v * v).sum::<f32>();
+            let h = self.hash(&corner[..dims]);
+            let g = if dims == 3 { grad3(h, x[0], x[1], x[2]) } else { grad4(h, x[0], x[1], x[2], x[3]) };
+            sum += falloff(0.6 - r) * g;
+        }
+        scale * sum
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn smootherstep(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+// the bump around a simplex corner
+fn falloff(t: f32) -> f32 {
+    if t < 0.0 { 0.0 } else { t * t * t * t }
+}
+
+fn grad1(h: usize, x: f32) -> f32 {
+    let g = (h & 15) as f32 / 7.5 - 1.0;
+    g * x
+}
+
+fn grad2(h: usize, x: f32, y: f32) -> f32 {
+    match h & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+// the 12 edges of a cube, as in improved perlin noise
+fn grad3(h: usize, x: f32, y: f32, z: f32) -> f32 {
+    let h = h & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+// the 32 edges of a tesseract
+fn grad4(h: usize, x: f32, y: f32, z: f32, w: f32) -> f32 {
+    let h = h & 31;
+    let a = if h < 24 { x } else { y };
+    let b = if h < 16 { y } else { z };
+    let c = if h < 8 { z } else { w };
+    (if h & 1 == 0 { a } else { -a }) + (if h & 2 == 0 { b } else { -b }) + (if h & 4 == 0 { c } else { -c })
+}
@@ -0,0 +1,129 @@
+use rand::{ Rng, SeedableRng, rngs::StdRng, seq::SliceRandom };
+use std::cell::{ Cell, RefCell };
+
+// one random stream per thread for the free helpers like randomf, the context
+// seeds it with its own seed, mixed so the two streams are not the same numbers
+thread_local! {
+    static SEED: Cell<u64> = const { Cell::new(0) };
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::seed_from_u64(0));
+}
+
+const HELPER_SEED_MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// restarts the stream of the free helpers
+pub fn set_seed(seed: u64) {
+    SEED.with(|s| s.set(seed));
+    RNG.with(|r| *r.borrow_mut() = StdRng::seed_from_u64(seed ^ HELPER_SEED_MIX));
+}
+
+pub fn get_seed() -> u64 {
+    SEED.with(|s| s.get())
+}
+
+// for everything rand can do with an Rng, on the stream of the free helpers
+pub fn with_rng<R, F: FnOnce(&mut StdRng) -> R>(f: F) -> R {
+    RNG.with(|r| f(&mut r.borrow_mut()))
+}
+
+// the seed from "--seed <n>" or "--seed=<n>" on the command line, otherwise a new one
+pub fn seed_from_args() -> u64 {
+    if let Some(value) = super::arg_value("seed") {
+        match value.parse::<u64>() {
+            Ok(seed) => return seed,
+            Err(_) => println!("--seed needs a whole number, using a random seed"),
+        }
+    }
+    rand::random()
+}
+
+// the random numbers of a context, a stream of its own so two contexts or a
+// replay do not take numbers from each other
+pub struct Random {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Random {
+
+    pub fn new(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn set_seed(&mut self, seed: u64) { *self = Self::new(seed) }
+    pub fn get_seed(&self) -> u64 { self.seed }
+    // back to the start of the stream
+    pub fn reseed(&mut self) { self.set_seed(self.seed) }
+    // for everything rand can do with an Rng
+    pub fn rng(&mut self) -> &mut StdRng { &mut self.rng }
+
+    // in [min, max)
+    pub fn float(&mut self, min: f32, max: f32) -> f32 {
+        min + self.rng.gen::<f32>() * (max - min)
+    }
+
+    // in [min, max)
+    pub fn uint(&mut self, min: usize, max: usize) -> usize {
+        if max <= min { return min }
+        self.rng.gen_range(min, max)
+    }
+
+    pub fn int(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min { return min }
+        self.rng.gen_range(min, max)
+    }
+
+    // true with probability p
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.rng.gen::<f32>() < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.choose(&mut self.rng)
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        items.shuffle(&mut self.rng)
+    }
+
+    // with mean 0 and standard deviation 1, by Box-Muller
+    pub fn gaussian(&mut self) -> f32 {
+        let u1 = self.rng.gen::<f32>().max(f32::MIN_POSITIVE);
+        let u2 = self.rng.gen::<f32>();
+        (-2.0 * u1.ln()).sqrt() * (crate::TWO_PI * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floats(random: &mut Random) -> Vec<f32> {
+        (0..8).map(|_| random.float(0.0, 1.0)).collect()
+    }
+
+    fn helper_floats() -> Vec<f32> {
+        (0..8).map(|_| with_rng(|r| r.gen::<f32>())).collect()
+    }
+
+    #[test]
+    fn reseed_repeats_the_stream() {
+        let mut random = Random::new(42);
+        let first = floats(&mut random);
+        assert_ne!(first, floats(&mut random));
+        random.reseed();
+        assert_eq!(first, floats(&mut random));
+        assert_eq!(random.get_seed(), 42);
+
+        set_seed(42);
+        let first = helper_floats();
+        set_seed(get_seed());
+        assert_eq!(first, helper_floats());
+    }
+
+    #[test]
+    fn context_and_helpers_differ() {
+        let mut random = Random::new(42);
+        set_seed(42);
+        assert_ne!(floats(&mut random), helper_floats());
+    }
+}
@@ -20,6 +20,9 @@ pub use constants::*;
 pub mod movie;
 pub use movie::*;
 
+pub mod creator;
+pub use creator::Geometry;
+
 pub mod traits;
 
 pub mod std;
@@ -49,8 +49,9 @@ macro_rules! fps60 {
 #[macro_export]
 macro_rules! get_fr {
     ($($body:tt)*) => {
-        use crate::funcs::helpers::randomf;
-        let check = randomf(0.0, 1.0) < 0.02;
+        // counted, not random, so it leaves the seeded numbers alone
+        static FRAMES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let check = FRAMES.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % 50 == 0;
         let time = std::time::Instant::now(); 
 
         { $($body)* }
@@ -1,5 +1,8 @@
 use crate::types::*;
 use crate::constants::*;
+use crate::Frustum;
+
+use nalgebra_glm as glm;
 
 pub struct OrthoCam {
     pub pos: Vec3,
@@ -38,6 +41,7 @@ pub struct HeadingProjCam {
     pub close_far: Vec2,
     pub pos: Vec3,
     pub heading: Vec3, 
+    pub up: Vec3,
 }
 
 impl HeadingProjCam {
@@ -48,13 +52,185 @@ impl HeadingProjCam {
             close_far: Vec2(0.1, far),
             pos: Vec3(0.0, 0.0, 0.0),
             heading: Vec3(0.0, 0.0, 1.0),
+            up: Vec3(0.0, 1.0, 0.0),
         }
     }
 
     pub fn get_vp(&self) -> Mat4 {
         let trans = Mat4::translate(-self.pos.0, -self.pos.1, -self.pos.2);
         let proj = Mat4::perspective(self.close_far.0, self.close_far.1, self.fov.0, self.fov.1);
-        let rot = Mat4::inv_rotation_from_heading(&E3, &self.heading);
+        let rot = Mat4::inv_rotation_from_frame(&self.heading, &self.up);
         proj * rot * trans
     }
+    pub fn get_frustum(&self) -> Frustum {
+        Frustum::from_mat4(&self.get_vp())
+    }
+}
+
+// in pixels, with the origin in the top left like the cursor position
+#[derive(Clone, Copy, Debug)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self { x: 0.0, y: 0.0, width, height }
+    }
+
+    pub fn aspect(&self) -> f32 {
+        if self.height == 0.0 { 1.0 } else { self.width / self.height }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: glm::Vec3,
+    // normalized
+    pub direction: glm::Vec3,
+}
+
+impl Ray {
+    pub fn at(&self, t: f32) -> glm::Vec3 { self.origin + self.direction * t }
+}
+
+pub trait Camera {
+    fn view(&self) -> glm::Mat4;
+    fn projection(&self) -> glm::Mat4;
+    fn viewport(&self) -> Viewport;
+    fn set_viewport(&mut self, viewport: Viewport);
+
+    fn position(&self) -> glm::Vec3;
+    fn forward(&self) -> glm::Vec3;
+    fn up(&self) -> glm::Vec3;
+    // moves and points the camera, so controllers work with any camera
+    fn set_pose(&mut self, position: glm::Vec3, forward: glm::Vec3, up: glm::Vec3);
+
+    fn view_projection(&self) -> glm::Mat4 {
+        self.projection() * self.view()
+    }
+
+    fn frustum(&self) -> Frustum {
+        Frustum::from_vp(&self.view_projection())
+    }
+
+    // the ray from the near plane through a point on the screen, in pixels
+    fn screen_to_world_ray(&self, x: f32, y: f32) -> Ray {
+        let vp = self.viewport();
+        let ndc_x = 2.0 * (x - vp.x) / vp.width - 1.0;
+        let ndc_y = 1.0 - 2.0 * (y - vp.y) / vp.height;
+        let inv = glm::inverse(&self.view_projection());
+        let unproject = |z: f32| {
+            let p = inv * glm::vec4(ndc_x, ndc_y, z, 1.0);
+            glm::vec3(p.x, p.y, p.z) / p.w
+        };
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+        Ray { origin: near, direction: glm::normalize(&(far - near)) }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PerspectiveCamera {
+    pub position: glm::Vec3,
+    pub forward: glm::Vec3,
+    pub up: glm::Vec3,
+    // vertical, in radians
+    pub fov: f32,
+    pub near: f32,
+    pub far: f32,
+    pub viewport: Viewport,
+}
+
+impl PerspectiveCamera {
+
+    pub fn new(fov: f32, near: f32, far: f32, viewport: Viewport) -> Self {
+        Self {
+            position: glm::vec3(0.0, 0.0, 0.0),
+            forward: -glm::Vec3::z(),
+            up: glm::Vec3::y(),
+            fov,
+            near,
+            far,
+            viewport,
+        }
+    }
+
+    pub fn look_at(&mut self, target: &glm::Vec3) {
+        self.forward = glm::normalize(&(target - self.position));
+    }
+}
+
+impl Camera for PerspectiveCamera {
+    fn view(&self) -> glm::Mat4 {
+        glm::look_at(&self.position, &(self.position + self.forward), &self.up)
+    }
+    fn projection(&self) -> glm::Mat4 {
+        glm::perspective(self.viewport.aspect(), self.fov, self.near, self.far)
+    }
+    fn viewport(&self) -> Viewport { self.viewport }
+    fn set_viewport(&mut self, viewport: Viewport) { self.viewport = viewport }
+    fn position(&self) -> glm::Vec3 { self.position }
+    fn forward(&self) -> glm::Vec3 { self.forward }
+    fn up(&self) -> glm::Vec3 { self.up }
+    fn set_pose(&mut self, position: glm::Vec3, forward: glm::Vec3, up: glm::Vec3) {
+        self.position = position;
+        self.forward = glm::normalize(&forward);
+        self.up = up;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OrthographicCamera {
+    pub position: glm::Vec3,
+    pub forward: glm::Vec3,
+    pub up: glm::Vec3,
+    // of the view volume in world units, the width follows from the aspect
+    pub height: f32,
+    pub near: f32,
+    pub far: f32,
+    pub viewport: Viewport,
+}
+
+impl OrthographicCamera {
+
+    pub fn new(height: f32, near: f32, far: f32, viewport: Viewport) -> Self {
+        Self {
+            position: glm::vec3(0.0, 0.0, 0.0),
+            forward: -glm::Vec3::z(),
+            up: glm::Vec3::y(),
+            height,
+            near,
+            far,
+            viewport,
+        }
+    }
+
+    pub fn look_at(&mut self, target: &glm::Vec3) {
+        self.forward = glm::normalize(&(target - self.position));
+    }
+}
+
+impl Camera for OrthographicCamera {
+    fn view(&self) -> glm::Mat4 {
+        glm::look_at(&self.position, &(self.position + self.forward), &self.up)
+    }
+    fn projection(&self) -> glm::Mat4 {
+        let h = self.height / 2.0;
+        let w = h * self.viewport.aspect();
+        glm::ortho(-w, w, -h, h, self.near, self.far)
+    }
+    fn viewport(&self) -> Viewport { self.viewport }
+    fn set_viewport(&mut self, viewport: Viewport) { self.viewport = viewport }
+    fn position(&self) -> glm::Vec3 { self.position }
+    fn forward(&self) -> glm::Vec3 { self.forward }
+    fn up(&self) -> glm::Vec3 { self.up }
+    fn set_pose(&mut self, position: glm::Vec3, forward: glm::Vec3, up: glm::Vec3) {
+        self.position = position;
+        self.forward = glm::normalize(&forward);
+        self.up = up;
+    }
 }
@@ -1,13 +1,38 @@
 use crate::{
     Window,
+    WindowConfig,
     Vec3,
     Key,
+    MouseButton,
+    Modifiers,
+    Event,
+    Input,
+    Transport,
+    Gamepads,
+    GamepadButton,
+    GamepadAxis,
     PainterDepricated,
-    Mat4
+    Mat4,
+    Lights,
+    Random,
+    Noise,
+    CullStats,
+    FrameStats,
+    Gui,
+    Camera,
+    PerspectiveCamera,
+    Viewport,
+    Actor,
+    FlyController,
+    Route,
+    RouteFrame,
+    RouteRider,
+    Banking,
 };
 
 use nalgebra_glm as glm;
 use std::time::SystemTime;
+use std::rc::Rc;
 
 trait Context {
     fn create() -> Self;
@@ -17,14 +42,33 @@ trait Context {
 pub struct MyContext {
     pub painter: PainterDepricated,
     pub window: Window, 
-    view_projection: Mat4,
-    view_projection_glm: glm::Mat4,
+    camera: Box<dyn Camera>,
+    // extra windows sharing the GL objects of the main one, with id index + 1
+    windows: Vec<Option<SecondaryWindow>>,
+    // acted by the MoviePlayer every frame before the movie updates
+    controller: Option<Box<dyn Actor>>,
     pub real_time: SystemTime,
-    pub pnoise: perlin_noise::PerlinNoise,
-    pub rng: rand::rngs::ThreadRng,
-    route: fn(f32) -> Vec3,
+    // both from the same seed, so a render can be made again
+    pub noise: Noise,
+    pub rng: Random,
+    pub lights: Lights,
+    pub input: Input,
+    pub transport: Transport,
+    pub gamepads: Gamepads,
+    // actors that cull by hand count into this, it is moved to last_cull_stats every frame
+    pub cull_stats: CullStats,
+    last_cull_stats: CullStats,
+    pub stats: FrameStats,
+    pub gui: Gui,
+    rider: RouteRider,
+    route_frame: RouteFrame,
     framerate: usize,
-    dt: f64,
+    // the frame time the framerate asks for
+    frame_time: f64,
+    // what the next timestep adds to the movie time, frame_time unless the loop says otherwise
+    step: f64,
+    // how far the shown frame is between the last two fixed steps
+    alpha: f64,
     movie_time: f64,
     location: Vec3,
     heading: Vec3,
@@ -33,30 +77,55 @@ pub struct MyContext {
 impl MyContext {
 
     pub fn create() -> Self {
-        let window = Window::set_up(500, 500, "PepMovie");
-        let view_projection = Mat4::identity();
-        let view_projection_glm = glm::identity();
+        Self::create_with(&WindowConfig::default())
+    }
+
+    pub fn create_with(config: &WindowConfig) -> Self {
+        let window = Window::from_config(config);
         let real_time = SystemTime::now();
         let movie_time = 0.0f64;
-        let route = |_| { Vec3(0.0, 0.0, 0.0) };
+        let route: Rc<dyn Route> = Rc::new(|_| glm::vec3(0.0, 0.0, 0.0));
+        let rider = RouteRider::new(route, 0.0, glm::Vec3::y());
+        let route_frame = rider.frame();
         let framerate = 60;
         let dt = 1.0 / framerate as f64;
         let mut painter = PainterDepricated::ne();
         painter.origin_top_left_projection(window.get_width(), window.get_height());
-        let pnoise = perlin_noise::PerlinNoise::new(); 
-        let rng = rand::thread_rng();
+        let seed = crate::seed_from_args();
+        println!("Seed: {}", seed);
+        let rng = Random::new(seed);
+        crate::set_seed(seed);
+        let noise = Noise::new(seed);
+        let viewport = Viewport::new(window.get_width() as f32, window.get_height() as f32);
         Self {
             window, 
-            view_projection,
-            view_projection_glm,
+            controller: None,
+            windows: vec![],
+            camera: Box::new(PerspectiveCamera::new(
+                crate::QUARTER_PI, 
+                0.1, 
+                100.0, 
+                viewport
+            )),
             framerate,
-            dt,
+            frame_time: dt,
+            step: dt,
+            alpha: 1.0,
             movie_time,
             real_time, 
-            route,
+            rider,
+            route_frame,
             painter,
-            pnoise,
+            noise,
             rng,
+            lights: Lights::ne(),
+            input: Input::ne(),
+            transport: Transport::ne(),
+            gamepads: Gamepads::ne(),
+            cull_stats: CullStats::default(),
+            last_cull_stats: CullStats::default(),
+            stats: FrameStats::ne(),
+            gui: Gui::ne(),
             location: Vec3(0.0, 0.0, 0.0),
             heading: Vec3(0.0, 0.0, 0.0)
         }
@@ -69,22 +138,163 @@ impl MyContext {
 
     pub fn set_window_size(&mut self, width: usize, height: usize) {
         self.window.set_size(width, height);
-        self.painter.origin_top_left_projection(width, height);
+        self.fit_to_window();
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        self.window.toggle_fullscreen();
+        self.fit_to_window();
     }
 
     pub fn set_framerate(&mut self, fr: usize) {
         self.framerate = fr;
-        self.dt = 1.0 / fr as f64;
+        self.frame_time = 1.0 / fr as f64;
+        self.step = self.frame_time;
+    }
+
+    // CAMERA
+
+    // the new camera gets the viewport of the window
+    pub fn set_camera<C: Camera + 'static>(&mut self, mut camera: C) {
+        camera.set_viewport(Viewport::new(self.get_width(), self.get_height()));
+        self.camera = Box::new(camera);
+    }
+    pub fn get_camera(&self) -> &dyn Camera { &*self.camera }
+    pub fn get_camera_mut(&mut self) -> &mut dyn Camera { &mut *self.camera }
+    pub fn set_controller<A: Actor + 'static>(&mut self, mut controller: A) {
+        self.clear_controller();
+        controller.start_acting(self);
+        self.controller = Some(Box::new(controller));
+    }
+
+    pub fn clear_controller(&mut self) {
+        if let Some(mut old) = self.controller.take() {
+            old.stop_acting(self);
+        }
+    }
+
+    // a FlyController starting at the current camera
+    pub fn debug_camera(&mut self) {
+        let fly = FlyController::from_camera(self.get_camera());
+        self.set_controller(fly);
+    }
+
+    pub fn act_controller(&mut self) {
+        if let Some(mut controller) = self.controller.take() {
+            controller.act(self);
+            if self.controller.is_none() {
+                self.controller = Some(controller);
+            }
+        }
     }
 
-    pub fn get_vp(&self) -> Mat4 { self.view_projection }
-    pub fn set_vp(&mut self, vp: &Mat4) { self.view_projection = *vp }
+    pub fn get_vp_glm(&self) -> glm::Mat4 { self.camera.view_projection() }
+    // for the actors that still use the custom Mat4
+    pub fn get_vp(&self) -> Mat4 { Mat4::from_glm(&self.camera.view_projection()) }
+
     pub fn get_width(&self) -> f32 { self.window.get_width() as f32 }
     pub fn get_height(&self) -> f32 { self.window.get_height() as f32 }
-    pub fn set_route(&mut self, route: fn(f32) -> Vec3) { self.route = route; }
-    pub fn get_route(&mut self) -> fn(f32) -> Vec3 { self.route }
+    pub fn get_pixel_ratio(&self) -> f32 { self.window.get_pixel_ratio() }
+    // accepts fn pointers, closures, splines or anything else implementing Route
+    pub fn set_route<R: Route + 'static>(&mut self, route: R) {
+        let banking = self.rider.banking;
+        self.rider = RouteRider::new(Rc::new(route), self.movie_time as f32, glm::Vec3::y());
+        self.rider.banking = banking;
+        self.route_frame = self.rider.frame();
+    }
+    pub fn get_route(&self) -> Rc<dyn Route> { self.rider.get_route() }
+    // rotation minimizing, with the up vector as normal
+    pub fn get_route_frame(&self) -> RouteFrame { self.route_frame }
+    pub fn get_up(&self) -> glm::Vec3 { self.route_frame.normal }
+    pub fn set_banking(&mut self, banking: Option<Banking>) { self.rider.banking = banking; }
     pub fn get_location(&self) -> Vec3 { self.location }
     pub fn get_heading(&self) -> Vec3 { self.heading }
+    pub fn get_cull_stats(&self) -> CullStats { self.last_cull_stats }
+
+    // WINDOWS
+
+    // opens another window that shares textures, buffers and shaders with the main
+    // one, it starts with a perspective camera of its own, see set_window_camera;
+    // only the main window waits for vsync, otherwise every window would halve the
+    // frame rate
+    pub fn add_window(&mut self, config: &WindowConfig) -> WindowId {
+        let window = Window::shared(config, &self.window);
+        window.set_swap_interval(0);
+        let viewport = Viewport::new(window.get_width() as f32, window.get_height() as f32);
+        let camera = Box::new(PerspectiveCamera::new(crate::QUARTER_PI, 0.1, 100.0, viewport));
+        self.window.make_currect();
+        let secondary = Some(SecondaryWindow { window, camera });
+        match self.windows.iter().position(|w| w.is_none()) {
+            Some(i) => { self.windows[i] = secondary; i + 1 }
+            None => { self.windows.push(secondary); self.windows.len() }
+        }
+    }
+
+    pub fn remove_window(&mut self, id: WindowId) {
+        if id == 0 {
+            println!("The main window can not be removed");
+            return
+        }
+        if let Some(w) = self.windows.get_mut(id - 1) {
+            *w = None;
+        }
+        self.window.make_currect();
+    }
+
+    // closes all windows but the main one
+    pub fn remove_windows(&mut self) {
+        self.windows.clear();
+        self.window.make_currect();
+    }
+
+    // the extra windows, without the main window 0
+    pub fn get_window_ids(&self) -> Vec<WindowId> {
+        self.windows.iter().enumerate().filter(|(_, w)| w.is_some()).map(|(i, _)| i + 1).collect()
+    }
+
+    pub fn get_window(&self, id: WindowId) -> Option<&Window> {
+        if id == 0 { return Some(&self.window) }
+        self.windows.get(id - 1).and_then(|w| w.as_ref()).map(|w| &w.window)
+    }
+
+    pub fn get_window_mut(&mut self, id: WindowId) -> Option<&mut Window> {
+        if id == 0 { return Some(&mut self.window) }
+        self.windows.get_mut(id - 1).and_then(|w| w.as_mut()).map(|w| &mut w.window)
+    }
+
+    pub fn set_window_camera<C: Camera + 'static>(&mut self, id: WindowId, mut camera: C) {
+        if id == 0 { return self.set_camera(camera) }
+        if let Some(secondary) = self.windows.get_mut(id - 1).and_then(|w| w.as_mut()) {
+            let (w, h) = (secondary.window.get_width(), secondary.window.get_height());
+            camera.set_viewport(Viewport::new(w as f32, h as f32));
+            secondary.camera = Box::new(camera);
+        }
+    }
+
+    // draws a frame into another window: its context is current and its camera is
+    // the one of the context while f runs, then its buffers are swapped; meshes and
+    // painters get vertex arrays for this window on their first draw, but framebuffers
+    // are not shared, so shadow maps and playlist fades are rendered in the main window
+    pub fn show_window<F: FnOnce(&mut MyContext)>(&mut self, id: WindowId, f: F) {
+        if id == 0 { return f(self) }
+        let mut secondary = match self.windows.get_mut(id - 1).and_then(|w| w.take()) {
+            Some(secondary) => secondary,
+            None => return,
+        };
+        secondary.window.make_currect();
+        std::mem::swap(&mut self.camera, &mut secondary.camera);
+        f(self);
+        std::mem::swap(&mut self.camera, &mut secondary.camera);
+        secondary.window.show();
+        self.windows[id - 1] = Some(secondary);
+        self.window.make_currect();
+    }
+
+    // LIGHTS
+
+    pub fn add_light(&mut self, light: crate::Light) -> usize { self.lights.add(light) }
+    pub fn remove_light(&mut self, id: usize) -> Option<crate::Light> { self.lights.remove(id) }
+    pub fn get_light_mut(&mut self, id: usize) -> Option<&mut crate::Light> { self.lights.get_mut(id) }
 
     // INPUT
  
@@ -96,6 +306,71 @@ impl MyContext {
         self.window.is_mouse_presses()
     }
 
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.window.is_mouse_button_pressed(button)
+    }
+
+    pub fn is_key_pressed_this_frame(&self, k: Key) -> bool { self.input.is_key_pressed_this_frame(k) }
+    pub fn is_key_released_this_frame(&self, k: Key) -> bool { self.input.is_key_released_this_frame(k) }
+    pub fn is_mouse_pressed_this_frame(&self, button: MouseButton) -> bool { self.input.is_button_pressed_this_frame(button) }
+    pub fn is_mouse_released_this_frame(&self, button: MouseButton) -> bool { self.input.is_button_released_this_frame(button) }
+    pub fn get_modifiers(&self) -> Modifiers { self.input.get_modifiers() }
+    pub fn get_scroll(&self) -> (f32, f32) { self.input.get_scroll() }
+    pub fn get_text(&self) -> &str { self.input.get_text() }
+    // on any connected gamepad
+    pub fn is_gamepad_pressed(&self, button: GamepadButton) -> bool { self.gamepads.is_pressed(button) }
+    pub fn is_gamepad_pressed_this_frame(&self, button: GamepadButton) -> bool { self.gamepads.is_pressed_this_frame(button) }
+    // of the first gamepad, with the deadzone applied
+    pub fn get_gamepad_axis(&self, axis: GamepadAxis) -> f32 { self.gamepads.get_axis(axis) }
+
+    // everything that happened since the last frame, in order
+    pub fn events(&self) -> &[Event] { self.input.events() }
+
+    // moves the window events into the input, once per frame
+    pub fn poll_events(&mut self) {
+        self.window.poll_events();
+        let mut events = self.window.take_events();
+        events.extend(self.gamepads.update());
+        let resized = events.iter().any(is_resize);
+        // only the input of the other windows, their sizes are their own
+        for id in self.get_window_ids() {
+            let secondary = self.windows[id - 1].as_mut().unwrap();
+            for event in secondary.window.take_events() {
+                match event {
+                    Event::Resized(..) | Event::FramebufferResized(..) => {
+                        let (w, h) = (secondary.window.get_width(), secondary.window.get_height());
+                        if w > 0 && h > 0 {
+                            secondary.camera.set_viewport(Viewport::new(w as f32, h as f32));
+                        }
+                    }
+                    Event::Key { .. } | Event::MouseButton { .. } | Event::Scroll(..) | Event::Char(_) => {
+                        events.push(event);
+                    }
+                    _ => {}
+                }
+            }
+            if secondary.window.is_close_requested() {
+                self.remove_window(id);
+            }
+        }
+        self.input.begin_frame(events);
+        if resized {
+            self.fit_to_window();
+        }
+    }
+
+    // the window already updated the viewport, the projections follow here
+    fn fit_to_window(&mut self) {
+        let (width, height) = (self.window.get_width(), self.window.get_height());
+        if width == 0 || height == 0 { return } // minimized
+        self.painter.origin_top_left_projection(width, height);
+        self.camera.set_viewport(Viewport::new(width as f32, height as f32));
+    }
+
+    pub fn was_resized(&self) -> bool {
+        self.input.events().iter().any(is_resize)
+    }
+
     pub fn get_cursor_pos(&self) -> (f32, f32) {
         self.window.get_cursor_pos() 
     }
@@ -114,23 +389,118 @@ impl MyContext {
         self.movie_time
     }
 
+    // the time the current update covers
     pub fn dt(&self) -> f64 {
-        self.dt
+        self.step
     }
 
+    // one over the framerate
+    pub fn frame_time(&self) -> f64 {
+        self.frame_time
+    }
+
+    pub fn get_framerate(&self) -> usize { self.framerate }
+
+    // set by the MoviePlayer, see LoopPolicy
+    pub fn set_step(&mut self, step: f64) { self.step = step; }
+    pub fn set_alpha(&mut self, alpha: f64) { self.alpha = alpha; }
+    // between 0 and 1, for interpolating between the states of the last two updates
+    pub fn get_alpha(&self) -> f64 { self.alpha }
+
     pub fn real_time(&self) -> f64 {
         self.real_time.elapsed().unwrap().as_secs_f64()
     }
 
     pub fn timestep(&mut self) {
-        self.movie_time += self.dt;
-        self.location = (self.route)(self.movie_time as f32);
-        self.heading = (self.route)(self.movie_time as f32 + crate::EPSILON) - self.location
+        self.movie_time += self.step;
+        self.follow_route();
+    }
+
+    // jumps, the route frame is carried along to keep its orientation
+    pub fn set_time(&mut self, t: f64) {
+        self.movie_time = t;
+        self.follow_route();
+    }
+
+    // for playing a movie again from the start, everything its setup adds is
+    // cleared, including the extra windows; the camera and its controller stay
+    pub fn reset_movie(&mut self) {
+        self.movie_time = 0.0;
+        self.remove_windows();
+        self.lights = Lights::ne();
+        let banking = self.rider.banking;
+        self.rider = RouteRider::new(Rc::new(|_| glm::vec3(0.0, 0.0, 0.0)), 0.0, glm::Vec3::y());
+        self.rider.banking = banking;
+        self.rng.reseed();
+        crate::set_seed(self.rng.get_seed());
+        self.follow_route();
+    }
+
+    // for the random numbers, the noise and the free helpers like randomf
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng.set_seed(seed);
+        crate::set_seed(seed);
+        self.noise = Noise::new(seed);
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.rng.get_seed()
+    }
+
+    fn follow_route(&mut self) {
+        let t = self.movie_time as f32;
+        self.route_frame = self.rider.advance(t);
+        self.location = Vec3::from_glm(&self.route_frame.position);
+        self.heading = Vec3::from_glm(&self.route_frame.tangent);
+        let (l, h) = (self.location, self.heading);
+        self.lights.follow(&glm::vec3(l.0, l.1, l.2), &glm::vec3(h.0, h.1, h.2));
+    }
+
+    // after the frame is shown, however many timesteps it took
+    pub fn end_frame(&mut self) {
+        self.last_cull_stats = self.cull_stats;
+        self.cull_stats.reset();
+    }
+
+    // before the movie updates, with the input of this frame
+    pub fn begin_gui(&mut self) {
+        let (mouse, width) = (self.get_cursor_pos(), self.get_width());
+        self.gui.begin(&self.input, mouse, width);
+    }
+
+    // right before the frame is shown, so the overlay ends up on top
+    pub fn record_frame(&mut self) {
+        // a frame time of 0 would make the overlay divide by 0, 60 fps is the default
+        self.stats.budget_ms = if self.frame_time() > 0.0 {
+            (self.frame_time() * 1000.0) as f32
+        } else {
+            1000.0 / 60.0
+        };
+        self.stats.record(self.movie_time);
+        if self.stats.show_overlay {
+            self.stats.paint_overlay();
+        }
     }
 
     pub fn new_frame(&mut self) {
         self.timestep();
+        self.gui.end();
+        self.record_frame();
         self.window.show();
+        self.end_frame();
+        self.poll_events();
+        self.stats.begin_frame();
+        self.begin_gui();
     }
 }
 
+pub type WindowId = usize;
+
+struct SecondaryWindow {
+    window: Window,
+    camera: Box<dyn Camera>,
+}
+
+fn is_resize(event: &Event) -> bool {
+    matches!(event, Event::Resized(..) | Event::FramebufferResized(..))
+}
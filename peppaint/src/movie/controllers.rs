@@ -0,0 +1,198 @@
+use crate::{
+    Actor,
+    MyContext,
+    Key,
+    Camera,
+};
+
+use nalgebra_glm as glm;
+
+// just short of straight up or down, where look_at breaks
+const MAX_PITCH: f32 = 1.55;
+
+// yaw and pitch from dragging with the left mouse button
+struct MouseLook {
+    yaw: f32,
+    pitch: f32,
+    sensitivity: f32,
+    last_cursor: Option<(f32, f32)>,
+}
+
+impl MouseLook {
+
+    fn from_forward(forward: &glm::Vec3, sensitivity: f32) -> Self {
+        let f = glm::normalize(forward);
+        Self {
+            yaw: f.x.atan2(-f.z),
+            pitch: f.y.clamp(-1.0, 1.0).asin(),
+            sensitivity,
+            last_cursor: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &MyContext, max_pitch: f32) {
+        if !ctx.is_mouse_pressed() || ctx.gui.wants_mouse() {
+            self.last_cursor = None;
+            return
+        }
+        let cursor = ctx.get_cursor_pos();
+        if let Some(last) = self.last_cursor {
+            // a drag over the full window height turns the camera by about 180 degrees
+            let scale = self.sensitivity * crate::PI / ctx.get_height();
+            self.yaw += (cursor.0 - last.0) * scale;
+            self.pitch -= (cursor.1 - last.1) * scale;
+            self.pitch = self.pitch.clamp(-max_pitch, max_pitch);
+        }
+        self.last_cursor = Some(cursor);
+    }
+
+    // yaw 0 looks down -z, like the cameras do by default
+    fn forward(&self) -> glm::Vec3 {
+        glm::vec3(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            -self.yaw.cos() * self.pitch.cos(),
+        )
+    }
+
+    fn right(&self) -> glm::Vec3 {
+        glm::vec3(self.yaw.cos(), 0.0, self.yaw.sin())
+    }
+}
+
+fn key_axis(ctx: &MyContext, positive: Key, negative: Key) -> f32 {
+    ctx.is_key_pressed(positive) as i32 as f32 - ctx.is_key_pressed(negative) as i32 as f32
+}
+
+// circles around a target, drag to rotate, W/S or Up/Down to zoom
+pub struct OrbitController {
+    pub target: glm::Vec3,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    // fraction of the distance per second
+    pub zoom_speed: f32,
+    look: MouseLook,
+}
+
+impl OrbitController {
+
+    pub fn new(target: glm::Vec3, distance: f32) -> Self {
+        Self {
+            target,
+            distance,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+            zoom_speed: 1.5,
+            look: MouseLook::from_forward(&-glm::Vec3::z(), 1.0),
+        }
+    }
+
+    // keeps the camera where it is, looking at the target
+    pub fn from_camera(camera: &dyn Camera, target: glm::Vec3) -> Self {
+        let offset = target - camera.position();
+        let mut this = Self::new(target, glm::length(&offset).max(0.1));
+        this.look = MouseLook::from_forward(&offset, 1.0);
+        this
+    }
+
+    pub fn set_sensitivity(&mut self, s: f32) { self.look.sensitivity = s }
+}
+
+impl Actor for OrbitController {
+    fn act(&mut self, ctx: &mut MyContext) {
+        let dt = ctx.dt() as f32;
+        self.look.update(ctx, MAX_PITCH);
+        let zoom = key_axis(ctx, Key::W, Key::S) + key_axis(ctx, Key::Up, Key::Down);
+        self.distance *= 1.0 - zoom * self.zoom_speed * dt;
+        // one scroll notch zooms as much as holding a key for a tenth of a second
+        self.distance *= 1.0 - ctx.get_scroll().1 * self.zoom_speed * 0.1;
+        self.distance = self.distance.max(self.min_distance).min(self.max_distance);
+
+        let forward = self.look.forward();
+        let position = self.target - forward * self.distance;
+        ctx.get_camera_mut().set_pose(position, forward, glm::Vec3::y());
+    }
+}
+
+// WASD to move where the camera looks, Space/E and LeftShift/Q to go up and down,
+// drag with the mouse to look around
+pub struct FlyController {
+    pub position: glm::Vec3,
+    // units per second, the arrow keys up and down change it
+    pub speed: f32,
+    look: MouseLook,
+}
+
+impl FlyController {
+
+    pub fn new(position: glm::Vec3, forward: glm::Vec3) -> Self {
+        Self { position, speed: 5.0, look: MouseLook::from_forward(&forward, 1.0) }
+    }
+
+    pub fn from_camera(camera: &dyn Camera) -> Self {
+        Self::new(camera.position(), camera.forward())
+    }
+
+    pub fn set_sensitivity(&mut self, s: f32) { self.look.sensitivity = s }
+}
+
+impl Actor for FlyController {
+    fn act(&mut self, ctx: &mut MyContext) {
+        let dt = ctx.dt() as f32;
+        self.look.update(ctx, MAX_PITCH);
+        let speed_change = key_axis(ctx, Key::Up, Key::Down);
+        self.speed = (self.speed * (1.0 + speed_change * dt)).max(0.01);
+
+        let forward = self.look.forward();
+        let right = self.look.right();
+        let mut movement = forward * key_axis(ctx, Key::W, Key::S) + right * key_axis(ctx, Key::D, Key::A);
+        movement.y += key_axis(ctx, Key::Space, Key::LeftShift) + key_axis(ctx, Key::E, Key::Q);
+        if glm::length(&movement) > 0.0 {
+            self.position += glm::normalize(&movement) * self.speed * dt;
+        }
+        ctx.get_camera_mut().set_pose(self.position, forward, glm::Vec3::y());
+    }
+}
+
+// walks over the horizontal plane at a fixed eye height, the pitch is clamped
+pub struct FirstPersonController {
+    pub position: glm::Vec3,
+    pub speed: f32,
+    // in radians, up and down from the horizon
+    pub max_pitch: f32,
+    look: MouseLook,
+}
+
+impl FirstPersonController {
+
+    pub fn new(position: glm::Vec3, forward: glm::Vec3) -> Self {
+        Self { 
+            position, 
+            speed: 3.0, 
+            max_pitch: 1.2,
+            look: MouseLook::from_forward(&forward, 1.0) 
+        }
+    }
+
+    pub fn from_camera(camera: &dyn Camera) -> Self {
+        Self::new(camera.position(), camera.forward())
+    }
+
+    pub fn set_sensitivity(&mut self, s: f32) { self.look.sensitivity = s }
+}
+
+impl Actor for FirstPersonController {
+    fn act(&mut self, ctx: &mut MyContext) {
+        let dt = ctx.dt() as f32;
+        self.look.update(ctx, self.max_pitch.abs().min(MAX_PITCH));
+
+        let right = self.look.right();
+        let ahead = glm::vec3(right.z, 0.0, -right.x);
+        let movement = ahead * key_axis(ctx, Key::W, Key::S) + right * key_axis(ctx, Key::D, Key::A);
+        if glm::length(&movement) > 0.0 {
+            self.position += glm::normalize(&movement) * self.speed * dt;
+        }
+        ctx.get_camera_mut().set_pose(self.position, self.look.forward(), glm::Vec3::y());
+    }
+}
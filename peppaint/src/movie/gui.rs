@@ -0,0 +1,401 @@
+use crate::{ Painter, Input, MouseButton };
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{ Hash, Hasher };
+
+// sizes in screen coordinates
+const ROW: f32 = 20.0;
+const PAD: f32 = 6.0;
+const MARGIN: f32 = 10.0;
+const FONT: f32 = 2.0;
+const PICKER: f32 = 100.0;
+const PICKER_CELLS: usize = 12;
+const HUE_WIDTH: f32 = 20.0;
+
+type Rgba = [f32; 4];
+const BACKGROUND: Rgba = [0.1, 0.1, 0.12, 0.85];
+const HEADER: Rgba = [0.22, 0.22, 0.28, 0.95];
+const WIDGET: Rgba = [0.25, 0.25, 0.3, 1.0];
+const HOVER: Rgba = [0.35, 0.35, 0.42, 1.0];
+const ACCENT: Rgba = [0.3, 0.6, 1.0, 1.0];
+const TEXT: Rgba = [0.95, 0.95, 0.95, 1.0];
+
+enum Shape {
+    Rect(f32, f32, f32, f32, Rgba),
+    Text(String, f32, f32, Rgba),
+}
+
+/*
+Usage, from Movie::show, as update can run more or less than once a frame:
+if ctx.gui.panel("Spiral") {
+    ctx.gui.slider("speed", &mut self.speed, 0.0, 10.0);
+    ctx.gui.checkbox("wire", &mut self.wire);
+    if ctx.gui.button("reset") { ... }
+}
+The widgets change the values right away and return whether they did, the
+panels are stacked in the top right corner and painted when the frame ends.
+*/
+pub struct Gui {
+    pub visible: bool,
+    pub width: f32,
+    // made when something is first painted
+    painter: Option<Painter>,
+    shapes: Vec<Shape>,
+    mouse: (f32, f32),
+    down: bool,
+    pressed: bool,
+    left: f32,
+    // the top of the next widget
+    cursor: f32,
+    // the widgets are in this panel, whose background is shapes[index] from top
+    panel: u64,
+    panel_background: Option<(usize, f32)>,
+    // the widget the mouse went down on, until it goes up
+    active: Option<u64>,
+    open: HashMap<u64, bool>,
+    // the open color picker and its hue, kept apart so greys don't lose it
+    picking: Option<u64>,
+    hue: f32,
+    hovered: bool,
+    wants_mouse: bool,
+}
+
+impl Gui {
+
+    pub fn ne() -> Self {
+        Self {
+            visible: true,
+            width: 220.0,
+            painter: None,
+            shapes: vec![],
+            mouse: (0.0, 0.0),
+            down: false,
+            pressed: false,
+            left: 0.0,
+            cursor: MARGIN,
+            panel: 0,
+            panel_background: None,
+            active: None,
+            open: HashMap::new(),
+            picking: None,
+            hue: 0.0,
+            hovered: false,
+            wants_mouse: false,
+        }
+    }
+
+    pub fn begin(&mut self, input: &Input, mouse: (f32, f32), screen_width: f32) {
+        self.shapes.clear();
+        self.mouse = mouse;
+        self.down = input.is_button_down(MouseButton::Left);
+        self.pressed = input.is_button_pressed_this_frame(MouseButton::Left);
+        self.left = screen_width - self.width - MARGIN;
+        self.cursor = MARGIN;
+        self.panel = 0;
+        self.panel_background = None;
+        self.hovered = false;
+    }
+
+    pub fn end(&mut self) {
+        self.close_panel();
+        self.wants_mouse = self.visible && (self.hovered || self.active.is_some());
+        if !self.down {
+            self.active = None;
+        }
+        if self.visible && !self.shapes.is_empty() {
+            self.paint();
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    // whether the mouse is over the gui or dragging in it, as of the last frame,
+    // so controllers can leave it alone
+    pub fn wants_mouse(&self) -> bool {
+        self.wants_mouse
+    }
+
+    // PANELS
+
+    // returns whether it is open, the widgets that follow are in it until the next panel
+    pub fn panel(&mut self, title: &str) -> bool {
+        if !self.visible { return false }
+        self.close_panel();
+        self.panel = 0;
+        let id = self.id(title);
+        self.panel = id;
+        if self.cursor > MARGIN {
+            self.cursor += PAD;
+        }
+        let top = self.cursor;
+        self.panel_background = Some((self.shapes.len(), top));
+        self.shapes.push(Shape::Rect(self.left, top, self.width, 0.0, BACKGROUND));
+
+        let (x, y, w, h) = (self.left, self.cursor, self.width, ROW);
+        let hot = self.inside(x, y, w, h);
+        let open = self.open.entry(id).or_insert(false);
+        if hot && self.pressed && self.active.is_none() {
+            *open = !*open;
+            self.active = Some(id);
+        }
+        let open = *open;
+        self.shapes.push(Shape::Rect(x, y, w, h, if hot { HOVER } else { HEADER }));
+        let title = format!("{} {}", if open { "-" } else { "+" }, title);
+        self.text(&title, x + PAD, y);
+        self.cursor += ROW + if open { PAD / 2.0 } else { 0.0 };
+        open
+    }
+
+    // WIDGETS
+
+    pub fn label(&mut self, text: &str) {
+        if !self.visible { return }
+        let (x, y, _, _) = self.row(ROW);
+        self.text(text, x, y);
+    }
+
+    // true when clicked
+    pub fn button(&mut self, label: &str) -> bool {
+        if !self.visible { return false }
+        let id = self.id(label);
+        let (x, y, w, h) = self.row(ROW);
+        let clicked = self.press(id, x, y, w, h);
+        let color = if self.active == Some(id) { ACCENT } else if self.inside(x, y, w, h) { HOVER } else { WIDGET };
+        self.shapes.push(Shape::Rect(x, y, w, h, color));
+        let tx = x + (w - Painter::text_width(label, FONT)) / 2.0;
+        self.text(label, tx, y);
+        clicked
+    }
+
+    pub fn checkbox(&mut self, label: &str, value: &mut bool) -> bool {
+        if !self.visible { return false }
+        let id = self.id(label);
+        let (x, y, w, h) = self.row(ROW);
+        let clicked = self.press(id, x, y, w, h);
+        if clicked {
+            *value = !*value;
+        }
+        let size = h - 6.0;
+        let color = if self.inside(x, y, w, h) { HOVER } else { WIDGET };
+        self.shapes.push(Shape::Rect(x, y + 3.0, size, size, color));
+        if *value {
+            self.shapes.push(Shape::Rect(x + 3.0, y + 6.0, size - 6.0, size - 6.0, ACCENT));
+        }
+        self.text(label, x + size + PAD, y);
+        clicked
+    }
+
+    // drag anywhere on it to set the value
+    pub fn slider(&mut self, label: &str, value: &mut f32, min: f32, max: f32) -> bool {
+        let text = format!("{}: {:.3}", label, value);
+        match self.slider_value(label, *value, min, max, &text) {
+            Some(v) if v != *value => { *value = v; true }
+            _ => false,
+        }
+    }
+
+    pub fn slider_int(&mut self, label: &str, value: &mut i32, min: i32, max: i32) -> bool {
+        let text = format!("{}: {}", label, value);
+        match self.slider_value(label, *value as f32, min as f32, max as f32, &text) {
+            Some(v) if v.round() as i32 != *value => { *value = v.round() as i32; true }
+            _ => false,
+        }
+    }
+
+    pub fn slider_usize(&mut self, label: &str, value: &mut usize, min: usize, max: usize) -> bool {
+        let text = format!("{}: {}", label, value);
+        match self.slider_value(label, *value as f32, min as f32, max as f32, &text) {
+            Some(v) if v.round() as usize != *value => { *value = v.round() as usize; true }
+            _ => false,
+        }
+    }
+
+    // rgb in [0, 1], clicking the swatch opens a saturation/value square and a hue strip
+    pub fn color(&mut self, label: &str, rgb: &mut [f32; 3]) -> bool {
+        if !self.visible { return false }
+        let id = self.id(label);
+        let (x, y, w, h) = self.row(ROW);
+        if self.press(id, x, y, w, h) {
+            if self.picking == Some(id) {
+                self.picking = None;
+            } else {
+                self.picking = Some(id);
+                self.hue = rgb_to_hsv(*rgb).0;
+            }
+        }
+        let swatch = w * 0.3;
+        self.text(label, x, y);
+        self.shapes.push(Shape::Rect(x + w - swatch, y + 2.0, swatch, h - 4.0, [rgb[0], rgb[1], rgb[2], 1.0]));
+        if self.picking != Some(id) { return false }
+
+        let (x, y, w, h) = self.row(PICKER);
+        let (_, mut s, mut v) = rgb_to_hsv(*rgb);
+        let square = w - HUE_WIDTH - PAD;
+        let hue_x = x + square + PAD;
+        let (square_id, hue_id) = (self.id(&format!("{} square", label)), self.id(&format!("{} hue", label)));
+        self.press(square_id, x, y, square, h);
+        self.press(hue_id, hue_x, y, HUE_WIDTH, h);
+
+        let mut changed = false;
+        if self.down && self.active == Some(square_id) {
+            s = ((self.mouse.0 - x) / square).clamp(0.0, 1.0);
+            v = 1.0 - ((self.mouse.1 - y) / h).clamp(0.0, 1.0);
+            changed = true;
+        }
+        if self.down && self.active == Some(hue_id) {
+            self.hue = ((self.mouse.1 - y) / h).clamp(0.0, 1.0);
+            changed = true;
+        }
+        if changed {
+            let new = hsv_to_rgb(self.hue, s, v);
+            changed = new != *rgb;
+            *rgb = new;
+        }
+
+        // the square and the strip are made of cells, the painter has one color per shape
+        let n = PICKER_CELLS as f32;
+        let (cw, ch) = (square / n, h / n);
+        for i in 0..PICKER_CELLS {
+            for j in 0..PICKER_CELLS {
+                let c = hsv_to_rgb(self.hue, (i as f32 + 0.5) / n, 1.0 - (j as f32 + 0.5) / n);
+                self.shapes.push(Shape::Rect(x + i as f32 * cw, y + j as f32 * ch, cw, ch, [c[0], c[1], c[2], 1.0]));
+            }
+        }
+        let segments = 2 * PICKER_CELLS;
+        let sh = h / segments as f32;
+        for k in 0..segments {
+            let c = hsv_to_rgb((k as f32 + 0.5) / segments as f32, 1.0, 1.0);
+            self.shapes.push(Shape::Rect(hue_x, y + k as f32 * sh, HUE_WIDTH, sh, [c[0], c[1], c[2], 1.0]));
+        }
+        self.shapes.push(Shape::Rect(x + s * square - 2.0, y + (1.0 - v) * h - 2.0, 4.0, 4.0, TEXT));
+        self.shapes.push(Shape::Rect(hue_x - 2.0, y + self.hue * h - 1.0, HUE_WIDTH + 4.0, 2.0, TEXT));
+        changed
+    }
+
+    // INNER
+
+    fn id(&self, label: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.panel.hash(&mut hasher);
+        label.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn inside(&self, x: f32, y: f32, w: f32, h: f32) -> bool {
+        let (mx, my) = self.mouse;
+        mx >= x && mx < x + w && my >= y && my < y + h
+    }
+
+    // makes the widget active when the mouse goes down on it
+    fn press(&mut self, id: u64, x: f32, y: f32, w: f32, h: f32) -> bool {
+        let pressed = self.pressed && self.active.is_none() && self.inside(x, y, w, h);
+        if pressed {
+            self.active = Some(id);
+        }
+        pressed
+    }
+
+    // the space for the next widget, inside the panel
+    fn row(&mut self, height: f32) -> (f32, f32, f32, f32) {
+        let (x, y, w) = (self.left + PAD, self.cursor, self.width - 2.0 * PAD);
+        self.cursor += height + PAD / 2.0;
+        if self.inside(self.left, y, self.width, height) {
+            self.hovered = true;
+        }
+        (x, y, w, height)
+    }
+
+    fn text(&mut self, text: &str, x: f32, y: f32) {
+        let ty = y + (ROW - Painter::text_height(FONT)) / 2.0;
+        self.shapes.push(Shape::Text(text.to_string(), x, ty, TEXT));
+    }
+
+    fn slider_value(&mut self, label: &str, value: f32, min: f32, max: f32, text: &str) -> Option<f32> {
+        if !self.visible { return None }
+        let id = self.id(label);
+        let (x, y, w, h) = self.row(ROW);
+        self.press(id, x, y, w, h);
+        let mut new = None;
+        if self.down && self.active == Some(id) {
+            let t = ((self.mouse.0 - x) / w).clamp(0.0, 1.0);
+            new = Some(min + t * (max - min));
+        }
+        let t = if max > min { ((new.unwrap_or(value) - min) / (max - min)).clamp(0.0, 1.0) } else { 0.0 };
+        let color = if self.inside(x, y, w, h) || self.active == Some(id) { HOVER } else { WIDGET };
+        self.shapes.push(Shape::Rect(x, y, w, h, color));
+        let fill = [ACCENT[0], ACCENT[1], ACCENT[2], 0.6];
+        self.shapes.push(Shape::Rect(x, y, t * w, h, fill));
+        self.text(text, x + PAD, y);
+        new
+    }
+
+    fn close_panel(&mut self) {
+        if let Some((index, top)) = self.panel_background.take() {
+            let height = self.cursor - top;
+            if let Shape::Rect(_, _, _, h, _) = &mut self.shapes[index] {
+                *h = height;
+            }
+            if self.inside(self.left, top, self.width, height) {
+                self.hovered = true;
+            }
+        }
+    }
+
+    fn paint(&mut self) {
+        if self.painter.is_none() {
+            let mut painter = Painter::ne();
+            painter.fit_screen();
+            self.painter = Some(painter);
+        }
+        let p = self.painter.as_mut().unwrap();
+        for shape in &self.shapes {
+            match shape {
+                Shape::Rect(x, y, w, h, c) => {
+                    p.color_alpha(c[0], c[1], c[2], c[3]);
+                    p.rect(*x, *y, *w, *h);
+                }
+                Shape::Text(text, x, y, c) => {
+                    p.color_alpha(c[0], c[1], c[2], c[3]);
+                    p.text(text, *x, *y, FONT);
+                }
+            }
+        }
+        p.paint();
+    }
+}
+
+// all in [0, 1]
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [f32; 3] {
+    let h = (h.fract() + 1.0).fract() * 6.0;
+    let i = h.floor();
+    let f = h - i;
+    let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+    match i as usize {
+        0 => [v, t, p],
+        1 => [q, v, p],
+        2 => [p, v, t],
+        3 => [p, q, v],
+        4 => [t, p, v],
+        _ => [v, p, q],
+    }
+}
+
+fn rgb_to_hsv(rgb: [f32; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+    let s = if max > 0.0 { d / max } else { 0.0 };
+    if d == 0.0 { return (0.0, s, max) }
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h / 6.0, s, max)
+}
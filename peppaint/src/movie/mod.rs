@@ -8,4 +8,19 @@ pub mod context;
 pub use context::*;
 
 pub mod actor;
-pub use actor::*;
\ No newline at end of file
+pub use actor::*;
+
+pub mod controllers;
+pub use controllers::*;
+
+pub mod route;
+pub use route::*;
+
+pub mod transport;
+pub use transport::*;
+
+pub mod playlist;
+pub use playlist::*;
+
+pub mod gui;
+pub use gui::*;
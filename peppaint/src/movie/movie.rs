@@ -1,7 +1,28 @@
-use crate::{ MyContext, Key };
-use std::time::SystemTime;
-use std::thread::sleep;
-use std::time::Duration;
+use crate::{ MyContext, Key, WindowConfig, WindowId, Transport, sleep_until, arg_value };
+use std::path::PathBuf;
+use std::time::{ Duration, Instant };
+
+// the longest real frame time the real time policies take into account, so a
+// breakpoint or a dragged window does not make the movie jump ahead
+const MAX_FRAME_TIME: f64 = 0.25;
+
+// how movie time relates to real time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoopPolicy {
+    // one update per frame, covering the real time the last frame took
+    RealTime,
+    // updates of exactly dt, as many as the real time asks for but at most max_steps
+    // per frame, which counts as 1 when it is 0; a frame shorter than dt may have no
+    // update at all, show gets the fraction of a step that is left as alpha
+    FixedStep { max_steps: usize },
+    // one update of dt per frame, so a slow frame slows the movie down; paced
+    // waits for the framerate, unpaced runs as fast as it can, for offline rendering
+    Lockstep { paced: bool },
+}
+
+impl Default for LoopPolicy {
+    fn default() -> Self { LoopPolicy::Lockstep { paced: true } }
+}
 
 pub struct MoviePlayer {}
     
@@ -11,47 +32,223 @@ impl MoviePlayer {
     Usage: 
     MoviePlayer::play::<YourMovieStruct>(); 
     */
-    pub fn play_debug<T: Movie>() {
+    pub fn play<T: Movie + 'static>() {
+        Self::run(&T::window_config(), T::loop_policy(), false, &setup::<T>);
+    }
+
+    // with the transport bound to keys, see Transport::handle_keys
+    pub fn play_debug<T: Movie + 'static>() {
+        Self::run(&T::window_config(), T::loop_policy(), true, &setup::<T>);
+    }
+
+    // debug mode with another loop policy than the movie asks for
+    pub fn play_with<T: Movie + 'static>(policy: LoopPolicy) {
+        Self::run(&T::window_config(), policy, true, &setup::<T>);
+    }
+
+    // setup is called again when seeking back
+    pub(crate) fn run(
+        config: &WindowConfig, 
+        policy: LoopPolicy, 
+        debug: bool, 
+        setup: &dyn Fn(&mut MyContext) -> Box<dyn AnyMovie>
+    ) {
 
         println!("Started movie");
 
-        let mut ctx = MyContext::create();
-        let mut movie = T::setup(&mut ctx);
+        let mut ctx = MyContext::create_with(config);
+        // F1 only toggles it in debug, so a plain play never shows the panels
+        ctx.gui.visible = debug;
+        // "--stats <file.csv|file.json>" keeps the timings of every frame and writes them at the end
+        let stats_path = arg_value("stats").map(PathBuf::from);
+        if stats_path.is_some() {
+            ctx.stats.set_capacity(None);
+        }
+        let mut movie = setup(&mut ctx);
 
         println!("Setted up");
-        let mut time = SystemTime::now();
+        let mut last_frame = Instant::now();
+        // real time that has not been simulated yet, for FixedStep
+        let mut accumulator = 0.0;
 
         while movie.is_playing() {
 
-            get_fr! {  
+            let frame_start = Instant::now();
+            let real_dt = (frame_start - last_frame).as_secs_f64().min(MAX_FRAME_TIME);
+            last_frame = frame_start;
+            ctx.stats.begin_frame();
+
+            if debug {
+                Transport::handle_keys(&mut ctx);
+                if ctx.is_key_pressed_this_frame(Key::F3) {
+                    ctx.stats.toggle_overlay();
+                }
+                if ctx.is_key_pressed_this_frame(Key::F1) {
+                    ctx.gui.toggle();
+                }
+            }
+            ctx.begin_gui();
+            if let Some(t) = ctx.transport.take_seek() {
+                Self::seek(&mut movie, &mut ctx, t, setup);
+                accumulator = 0.0;
+            }
 
-                movie.update(&mut ctx);
-                movie.show(&mut ctx);
+            // the camera moves once per shown frame with the real time it took, so
+            // it keeps moving while paused or slowed down by the transport speed
+            ctx.set_step(real_dt);
+            ctx.act_controller();
 
-                ctx.window.show();
-                ctx.timestep();
-                if ctx.window.should_close() ||
-                ctx.is_key_pressed(Key::Escape) {
-                    break;
+            let speed = ctx.transport.speed;
+            if !ctx.transport.take_advance() {
+                ctx.set_alpha(1.0);
+                accumulator = 0.0;
+            } else if ctx.transport.paused {
+                // a single step
+                ctx.set_step(ctx.frame_time());
+                Self::update(&mut movie, &mut ctx);
+                ctx.set_alpha(1.0);
+            } else {
+                match policy {
+                    LoopPolicy::RealTime => {
+                        ctx.set_step(real_dt * speed);
+                        Self::update(&mut movie, &mut ctx);
+                        ctx.set_alpha(1.0);
+                    }
+                    LoopPolicy::FixedStep { max_steps } => {
+                        let max_steps = max_steps.max(1);
+                        let dt = ctx.frame_time();
+                        ctx.set_step(dt);
+                        accumulator += real_dt * speed;
+                        let mut steps = 0;
+                        while accumulator >= dt && steps < max_steps {
+                            Self::update(&mut movie, &mut ctx);
+                            accumulator -= dt;
+                            steps += 1;
+                        }
+                        // too far behind, let it go instead of spiraling
+                        if steps == max_steps {
+                            accumulator = accumulator.min(dt);
+                        }
+                        ctx.set_alpha(accumulator / dt);
+                    }
+                    LoopPolicy::Lockstep { .. } => {
+                        ctx.set_step(ctx.frame_time() * speed);
+                        Self::update(&mut movie, &mut ctx);
+                        ctx.set_alpha(1.0);
+                    }
                 }
+            }
+
+            if let Some(start) = ctx.transport.loop_back(ctx.time()) {
+                Self::seek(&mut movie, &mut ctx, start, setup);
+                accumulator = 0.0;
+            }
 
+            let alpha = ctx.get_alpha() as f32;
+            movie.show_interpolated(&mut ctx, alpha);
+            for id in ctx.get_window_ids() {
+                ctx.show_window(id, |ctx| movie.show_window(ctx, id));
             }
 
-            let dt = ctx.dt();
-            let elapsed = time.elapsed().unwrap().as_secs_f64();
-            if dt > elapsed {
-                let time_to_sleep = ctx.dt() - elapsed;
-                sleep(Duration::from_secs_f64(time_to_sleep))
+            ctx.gui.end();
+            ctx.record_frame();
+            ctx.window.show();
+            ctx.end_frame();
+            ctx.poll_events();
+            if ctx.window.should_close() ||
+            ctx.is_key_pressed(Key::Escape) {
+                break;
             }
-            time = SystemTime::now();
+
+            // the real time policies are paced by vsync or run free
+            if policy == (LoopPolicy::Lockstep { paced: true }) {
+                sleep_until(last_frame + Duration::from_secs_f64(ctx.frame_time()));
+            }
+        }
+
+        if let Some(path) = stats_path {
+            match ctx.stats.export(&path) {
+                Ok(()) => println!("Wrote frame stats to {}", path.display()),
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+
+    fn update(movie: &mut Box<dyn AnyMovie>, ctx: &mut MyContext) {
+        movie.update(ctx);
+        ctx.timestep();
+    }
+
+    // asks the movie to jump, otherwise it is simulated to t in steps of the frame
+    // time, from a fresh setup when going back
+    fn seek(
+        movie: &mut Box<dyn AnyMovie>, 
+        ctx: &mut MyContext, 
+        t: f64, 
+        setup: &dyn Fn(&mut MyContext) -> Box<dyn AnyMovie>
+    ) {
+        if movie.seek(ctx, t) {
+            ctx.set_time(t);
+            return
         }
+        if t < ctx.time() {
+            ctx.reset_movie();
+            *movie = setup(ctx);
+        }
+        simulate_to(&mut **movie, ctx, t);
+        println!("Sought {:.2}s", ctx.time());
     }
 }
 
 pub trait Movie {
+    // how the window is made, before setup
+    fn window_config() -> WindowConfig { WindowConfig::default() }
+    fn loop_policy() -> LoopPolicy { LoopPolicy::default() }
     fn setup(ctx: &mut MyContext) -> Self;
     fn update(&mut self, _ctx: &mut MyContext) {}
     fn show(&mut self, _ctx: &mut MyContext) {}
+    // alpha is how far the frame is past the last update, towards the next one,
+    // which only matters for LoopPolicy::FixedStep
+    fn show_interpolated(&mut self, ctx: &mut MyContext, _alpha: f32) { self.show(ctx) }
+    // for every window added with MyContext::add_window, after show
+    fn show_window(&mut self, _ctx: &mut MyContext, _id: WindowId) {}
     fn is_playing(&self) -> bool { true }
+    // puts the movie in its state at time t and returns true, or returns false to
+    // have the MoviePlayer simulate to t, which needs a deterministic update and a
+    // setup that can run again
+    fn seek(&mut self, _ctx: &mut MyContext, _t: f64) -> bool { false }
+}
+
+// the object safe part of Movie, so the MoviePlayer and the Playlist can hold
+// any movie
+pub(crate) trait AnyMovie {
+    fn update(&mut self, ctx: &mut MyContext);
+    fn show_interpolated(&mut self, ctx: &mut MyContext, alpha: f32);
+    fn show_window(&mut self, ctx: &mut MyContext, id: WindowId);
+    fn is_playing(&self) -> bool;
+    fn seek(&mut self, ctx: &mut MyContext, t: f64) -> bool;
+}
+
+struct MovieBox<T: Movie>(T);
+
+impl<T: Movie> AnyMovie for MovieBox<T> {
+    fn update(&mut self, ctx: &mut MyContext) { self.0.update(ctx) }
+    fn show_interpolated(&mut self, ctx: &mut MyContext, alpha: f32) { self.0.show_interpolated(ctx, alpha) }
+    fn show_window(&mut self, ctx: &mut MyContext, id: WindowId) { self.0.show_window(ctx, id) }
+    fn is_playing(&self) -> bool { self.0.is_playing() }
+    fn seek(&mut self, ctx: &mut MyContext, t: f64) -> bool { self.0.seek(ctx, t) }
+}
+
+pub(crate) fn setup<T: Movie + 'static>(ctx: &mut MyContext) -> Box<dyn AnyMovie> {
+    Box::new(MovieBox(T::setup(ctx)))
 }
 
+// updates in steps of the frame time, without showing
+pub(crate) fn simulate_to(movie: &mut dyn AnyMovie, ctx: &mut MyContext, t: f64) {
+    let dt = ctx.frame_time();
+    ctx.set_step(dt);
+    while ctx.time() + dt / 2.0 < t {
+        movie.update(ctx);
+        ctx.timestep();
+    }
+}
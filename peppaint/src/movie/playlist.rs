@@ -0,0 +1,313 @@
+use crate::{
+    MyContext,
+    Movie,
+    MoviePlayer,
+    LoopPolicy,
+    WindowConfig,
+    WindowId,
+    Key,
+    Framebuffer,
+    ShaderProgram,
+    VertexArrayObject,
+};
+use crate::movie::movie::{ AnyMovie, setup, simulate_to };
+use std::rc::Rc;
+
+type Setup = Rc<dyn Fn(&mut MyContext) -> Box<dyn AnyMovie>>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    Cut,
+    // over a number of seconds of the new movie
+    CrossFade(f64),
+    // from left to right
+    Wipe(f64),
+}
+
+struct PlaylistEntry {
+    name: String,
+    setup: Setup,
+    // switches to the next movie after this many seconds
+    duration: Option<f64>,
+}
+
+// the last frame of the old movie and the frames of the new one while a transition runs
+struct Fade {
+    from: Framebuffer,
+    to: Framebuffer,
+}
+
+// several movies in one window, each starts with a fresh movie time, lights and
+// route and gets the next when its duration is over, when it stops playing or on
+// the next key; use as
+// Playlist::new().add::<Intro>().add_for::<Piece>(60.0).transition(Transition::CrossFade(2.0)).play_debug()
+pub struct Playlist {
+    entries: Vec<PlaylistEntry>,
+    pub transition: Transition,
+    pub next_key: Option<Key>,
+    pub previous_key: Option<Key>,
+    // starts over after the last movie
+    pub looping: bool,
+    window_config: WindowConfig,
+    loop_policy: LoopPolicy,
+    current: usize,
+    movie: Option<Box<dyn AnyMovie>>,
+    finished: bool,
+    fade: Option<Fade>,
+    // made once there is a context
+    program: Option<ShaderProgram>,
+    vao: Option<VertexArrayObject>,
+}
+
+impl Default for Playlist {
+    fn default() -> Self { Self::new() }
+}
+
+impl Playlist {
+
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            transition: Transition::Cut,
+            next_key: Some(Key::N),
+            previous_key: Some(Key::B),
+            looping: false,
+            window_config: WindowConfig::default(),
+            loop_policy: LoopPolicy::default(),
+            current: 0,
+            movie: None,
+            finished: false,
+            fade: None,
+            program: None,
+            vao: None,
+        }
+    }
+
+    // plays until it stops playing or is skipped
+    pub fn add<T: Movie + 'static>(mut self) -> Self {
+        self.push::<T>(None);
+        self
+    }
+
+    pub fn add_for<T: Movie + 'static>(mut self, seconds: f64) -> Self {
+        self.push::<T>(Some(seconds));
+        self
+    }
+
+    fn push<T: Movie + 'static>(&mut self, duration: Option<f64>) {
+        self.entries.push(PlaylistEntry {
+            name: std::any::type_name::<T>().to_string(),
+            setup: Rc::new(setup::<T>),
+            duration,
+        });
+    }
+
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    pub fn keys(mut self, next: Option<Key>, previous: Option<Key>) -> Self {
+        self.next_key = next;
+        self.previous_key = previous;
+        self
+    }
+
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn window_config(mut self, config: WindowConfig) -> Self {
+        self.window_config = config;
+        self
+    }
+
+    pub fn loop_policy(mut self, policy: LoopPolicy) -> Self {
+        self.loop_policy = policy;
+        self
+    }
+
+    pub fn play(self) {
+        self.play_in(false);
+    }
+
+    pub fn play_debug(self) {
+        self.play_in(true);
+    }
+
+    fn play_in(self, debug: bool) {
+        if self.entries.is_empty() {
+            println!("Nothing to play, the playlist is empty");
+            return
+        }
+        let config = self.window_config.clone();
+        let policy = self.loop_policy;
+        // the playlist seeks by itself, so this runs once
+        let playlist = std::cell::RefCell::new(Some(self));
+        MoviePlayer::run(&config, policy, debug, &|_| {
+            let playlist: Box<dyn AnyMovie> = Box::new(playlist.borrow_mut().take().expect("A playlist can not be set up twice"));
+            playlist
+        });
+    }
+
+    pub fn get_current(&self) -> usize { self.current }
+    pub fn get_current_name(&self) -> &str { &self.entries[self.current].name }
+
+    // SWITCHING
+
+    fn start(&mut self, ctx: &mut MyContext, index: usize) {
+        if self.transition != Transition::Cut && self.movie.is_some() {
+            self.capture_last_frame(ctx);
+        }
+        self.movie = None;
+        ctx.clear_controller();
+        // also closes the windows the outgoing movie added, the next one adds its own
+        ctx.reset_movie();
+        self.current = index;
+        println!("Playing {} ({}/{})", self.entries[index].name, index + 1, self.entries.len());
+        self.movie = Some((self.entries[index].setup)(ctx));
+    }
+
+    fn next(&mut self, ctx: &mut MyContext) {
+        if self.current + 1 < self.entries.len() {
+            self.start(ctx, self.current + 1);
+        } else if self.looping {
+            self.start(ctx, 0);
+        } else {
+            self.finished = true;
+        }
+    }
+
+    fn previous(&mut self, ctx: &mut MyContext) {
+        let index = if self.current == 0 { self.entries.len() - 1 } else { self.current - 1 };
+        self.start(ctx, index);
+    }
+
+    // TRANSITIONS
+
+    fn transition_time(&self) -> f64 {
+        match self.transition {
+            Transition::Cut => 0.0,
+            Transition::CrossFade(t) | Transition::Wipe(t) => t,
+        }
+    }
+
+    fn capture_last_frame(&mut self, ctx: &mut MyContext) {
+        let (w, h) = (ctx.window.get_framebuffer_width(), ctx.window.get_framebuffer_height());
+        let fits = match &self.fade {
+            Some(fade) => fade.from.get_width() == w && fade.from.get_height() == h,
+            None => false,
+        };
+        if !fits {
+            self.fade = Some(Fade { from: Framebuffer::with_color(w, h), to: Framebuffer::with_color(w, h) });
+        }
+        let fade = self.fade.as_ref().unwrap();
+        fade.from.clear();
+        if let Some(movie) = self.movie.as_mut() {
+            movie.show_interpolated(ctx, 1.0);
+        }
+        Framebuffer::bind_default();
+        ctx.window.update_viewport();
+    }
+
+    fn is_fading(&self, ctx: &MyContext) -> bool {
+        self.fade.is_some() && ctx.time() < self.transition_time()
+    }
+
+    fn show_fade(&mut self, ctx: &mut MyContext, alpha: f32) {
+        let progress = (ctx.time() / self.transition_time()).min(1.0) as f32;
+        let kind = match self.transition { Transition::Wipe(_) => 1, _ => 0 };
+        let fade = self.fade.as_ref().unwrap();
+        fade.to.clear();
+        if let Some(movie) = self.movie.as_mut() {
+            movie.show_interpolated(ctx, alpha);
+        }
+        Framebuffer::bind_default();
+        ctx.window.update_viewport();
+
+        if self.program.is_none() {
+            self.program = Some(crate::std::shaders::transition());
+            self.vao = Some(VertexArrayObject::new_static());
+        }
+        let program = self.program.as_mut().unwrap();
+        program.bind();
+        program.set_int("uFrom", 0);
+        program.set_int("uTo", 1);
+        program.set_float("uProgress", progress);
+        program.set_int("uKind", kind);
+        fade.from.get_color().unwrap().bind_to_unit(0);
+        fade.to.get_color().unwrap().bind_to_unit(1);
+        self.vao.as_ref().unwrap().bind();
+        crate::gl_disable_depth();
+        crate::count_draw(3);
+        unsafe { gl::DrawArrays(gl::TRIANGLES, 0, 3); }
+        crate::gl_enable_depth();
+    }
+}
+
+impl AnyMovie for Playlist {
+
+    fn update(&mut self, ctx: &mut MyContext) {
+        if self.movie.is_none() {
+            self.start(ctx, 0);
+        }
+
+        let over = match self.entries[self.current].duration {
+            Some(duration) => ctx.time() >= duration,
+            None => false,
+        };
+        let stopped = !self.movie.as_ref().unwrap().is_playing();
+        if over || stopped {
+            self.next(ctx);
+        }
+
+        if let Some(movie) = self.movie.as_mut() {
+            movie.update(ctx);
+        }
+    }
+
+    // the keys are read here, once per frame, since a frame can have any number
+    // of updates and none while paused
+    fn show_interpolated(&mut self, ctx: &mut MyContext, alpha: f32) {
+        if self.movie.is_none() {
+            self.start(ctx, 0);
+        }
+        let next = self.next_key.map(|k| ctx.is_key_pressed_this_frame(k)).unwrap_or(false);
+        let previous = self.previous_key.map(|k| ctx.is_key_pressed_this_frame(k)).unwrap_or(false);
+        if previous {
+            self.previous(ctx);
+        } else if next {
+            self.next(ctx);
+        }
+
+        if self.is_fading(ctx) {
+            self.show_fade(ctx, alpha);
+        } else if let Some(movie) = self.movie.as_mut() {
+            movie.show_interpolated(ctx, alpha);
+        }
+    }
+
+    fn show_window(&mut self, ctx: &mut MyContext, id: WindowId) {
+        if let Some(movie) = self.movie.as_mut() {
+            movie.show_window(ctx, id);
+        }
+    }
+
+    fn is_playing(&self) -> bool { !self.finished }
+
+    // within the current movie, which is set up again when going back
+    fn seek(&mut self, ctx: &mut MyContext, t: f64) -> bool {
+        let movie = match self.movie.as_mut() {
+            Some(movie) => movie,
+            None => return false,
+        };
+        if movie.seek(ctx, t) { return true }
+        if t < ctx.time() {
+            ctx.reset_movie();
+            self.movie = Some((self.entries[self.current].setup)(ctx));
+        }
+        simulate_to(&mut **self.movie.as_mut().unwrap(), ctx, t);
+        true
+    }
+}
@@ -0,0 +1,363 @@
+use nalgebra_glm as glm;
+use std::rc::Rc;
+
+// step for the numerical derivatives of routes that only know their position
+const DERIVATIVE_STEP: f32 = 0.001;
+
+// a position and orthonormal axes along a route
+#[derive(Clone, Copy, Debug)]
+pub struct RouteFrame {
+    pub position: glm::Vec3,
+    pub tangent: glm::Vec3,
+    pub normal: glm::Vec3,
+    pub binormal: glm::Vec3,
+}
+
+// a path through space over time, only the position is required but routes
+// that know their derivatives give smoother and cheaper frames
+pub trait Route {
+    fn position(&self, t: f32) -> glm::Vec3;
+
+    fn velocity(&self, t: f32) -> glm::Vec3 {
+        let h = DERIVATIVE_STEP;
+        (self.position(t + h) - self.position(t - h)) / (2.0 * h)
+    }
+
+    fn acceleration(&self, t: f32) -> glm::Vec3 {
+        let h = DERIVATIVE_STEP;
+        (self.velocity(t + h) - self.velocity(t - h)) / (2.0 * h)
+    }
+
+    // zero where the route stands still
+    fn tangent(&self, t: f32) -> glm::Vec3 {
+        normalize_or_zero(&self.velocity(t))
+    }
+
+    // the Frenet normal, pointing to the center of curvature, on straight
+    // stretches it is any vector perpendicular to the tangent
+    fn normal(&self, t: f32) -> glm::Vec3 {
+        let tangent = self.tangent(t);
+        let a = self.acceleration(t);
+        let n = a - tangent * glm::dot(&a, &tangent);
+        if glm::length(&n) > 1e-6 {
+            glm::normalize(&n)
+        } else {
+            perpendicular(&tangent)
+        }
+    }
+
+    fn binormal(&self, t: f32) -> glm::Vec3 {
+        glm::cross(&self.tangent(t), &self.normal(t))
+    }
+
+    fn frame(&self, t: f32) -> RouteFrame {
+        let tangent = self.tangent(t);
+        let normal = self.normal(t);
+        RouteFrame {
+            position: self.position(t),
+            tangent,
+            normal,
+            binormal: glm::cross(&tangent, &normal),
+        }
+    }
+
+    fn curvature(&self, t: f32) -> f32 {
+        let v = self.velocity(t);
+        let speed = glm::length(&v);
+        if speed < 1e-6 { return 0.0 }
+        glm::length(&glm::cross(&v, &self.acceleration(t))) / (speed * speed * speed)
+    }
+}
+
+// closures and fn pointers
+impl<F: Fn(f32) -> glm::Vec3> Route for F {
+    fn position(&self, t: f32) -> glm::Vec3 { self(t) }
+}
+
+// a route from a function and its derivative
+pub struct AnalyticRoute<F, G> {
+    pub position: F,
+    pub velocity: G,
+}
+
+impl<F, G> AnalyticRoute<F, G> 
+where F: Fn(f32) -> glm::Vec3, G: Fn(f32) -> glm::Vec3 {
+    pub fn new(position: F, velocity: G) -> Self {
+        Self { position, velocity }
+    }
+}
+
+impl<F, G> Route for AnalyticRoute<F, G> 
+where F: Fn(f32) -> glm::Vec3, G: Fn(f32) -> glm::Vec3 {
+    fn position(&self, t: f32) -> glm::Vec3 { (self.position)(t) }
+    fn velocity(&self, t: f32) -> glm::Vec3 { (self.velocity)(t) }
+    fn acceleration(&self, t: f32) -> glm::Vec3 {
+        let h = DERIVATIVE_STEP;
+        ((self.velocity)(t + h) - (self.velocity)(t - h)) / (2.0 * h)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplineKind {
+    // passes through every control point
+    CatmullRom,
+    // passes through every third point, the two in between are the handles
+    Bezier,
+    // smoothest, but passes through none of the points
+    BSpline,
+}
+
+// a piecewise cubic curve, t runs from 0 to n_segments with one segment per unit
+pub struct Spline {
+    pub kind: SplineKind,
+    pub points: Vec<glm::Vec3>,
+    pub closed: bool,
+}
+
+// p(u) = [1, u, u^2, u^3] * BASIS * [p0, p1, p2, p3]
+const CATMULL_ROM_BASIS: [[f32; 4]; 4] = [
+    [0.0, 1.0, 0.0, 0.0],
+    [-0.5, 0.0, 0.5, 0.0],
+    [1.0, -2.5, 2.0, -0.5],
+    [-0.5, 1.5, -1.5, 0.5],
+];
+const BEZIER_BASIS: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [-3.0, 3.0, 0.0, 0.0],
+    [3.0, -6.0, 3.0, 0.0],
+    [-1.0, 3.0, -3.0, 1.0],
+];
+const B_SPLINE_BASIS: [[f32; 4]; 4] = [
+    [1.0 / 6.0, 4.0 / 6.0, 1.0 / 6.0, 0.0],
+    [-0.5, 0.0, 0.5, 0.0],
+    [0.5, -1.0, 0.5, 0.0],
+    [-1.0 / 6.0, 0.5, -0.5, 1.0 / 6.0],
+];
+
+impl Spline {
+
+    pub fn catmull_rom(points: Vec<glm::Vec3>, closed: bool) -> Self {
+        Self { kind: SplineKind::CatmullRom, points, closed }
+    }
+
+    // needs 3k + 1 points, closed curves can repeat the first point at the end
+    pub fn bezier(points: Vec<glm::Vec3>) -> Self {
+        if points.len() < 4 || !(points.len() - 1).is_multiple_of(3) {
+            println!("A Bezier spline needs 3k + 1 points, the last ones are ignored");
+        }
+        Self { kind: SplineKind::Bezier, points, closed: false }
+    }
+
+    pub fn b_spline(points: Vec<glm::Vec3>, closed: bool) -> Self {
+        Self { kind: SplineKind::BSpline, points, closed }
+    }
+
+    pub fn n_segments(&self) -> usize {
+        let n = self.points.len();
+        match (self.kind, self.closed) {
+            (SplineKind::Bezier, _) => if n < 4 { 0 } else { (n - 1) / 3 },
+            (_, true) => n,
+            (SplineKind::CatmullRom, false) => n.saturating_sub(1),
+            (SplineKind::BSpline, false) => n.saturating_sub(3),
+        }
+    }
+
+    fn basis(&self) -> &'static [[f32; 4]; 4] {
+        match self.kind {
+            SplineKind::CatmullRom => &CATMULL_ROM_BASIS,
+            SplineKind::Bezier => &BEZIER_BASIS,
+            SplineKind::BSpline => &B_SPLINE_BASIS,
+        }
+    }
+
+    // the four control points of the segment t falls in and the local u in [0, 1]
+    fn segment(&self, t: f32) -> Option<([glm::Vec3; 4], f32)> {
+        let n_segments = self.n_segments();
+        if n_segments == 0 { return None }
+        let t = if self.closed {
+            t.rem_euclid(n_segments as f32)
+        } else {
+            t.max(0.0).min(n_segments as f32)
+        };
+        let i = (t.floor() as usize).min(n_segments - 1);
+        let u = t - i as f32;
+
+        let n = self.points.len() as isize;
+        let point = |j: isize| {
+            let j = if self.closed { j.rem_euclid(n) } else { j.max(0).min(n - 1) };
+            self.points[j as usize]
+        };
+        let i = i as isize;
+        let p = match self.kind {
+            SplineKind::CatmullRom => [point(i - 1), point(i), point(i + 1), point(i + 2)],
+            SplineKind::Bezier => [point(3 * i), point(3 * i + 1), point(3 * i + 2), point(3 * i + 3)],
+            SplineKind::BSpline => [point(i), point(i + 1), point(i + 2), point(i + 3)],
+        };
+        Some((p, u))
+    }
+
+    fn evaluate(&self, t: f32, powers: fn(f32) -> [f32; 4]) -> glm::Vec3 {
+        let (p, u) = match self.segment(t) {
+            Some(s) => s,
+            None => return self.points.first().cloned().unwrap_or_else(glm::zero)
+        };
+        let powers = powers(u);
+        let basis = self.basis();
+        let mut result = glm::vec3(0.0, 0.0, 0.0);
+        for (j, point) in p.iter().enumerate() {
+            let weight: f32 = (0..4).map(|k| powers[k] * basis[k][j]).sum();
+            result += point * weight;
+        }
+        result
+    }
+}
+
+impl Route for Spline {
+    fn position(&self, t: f32) -> glm::Vec3 {
+        self.evaluate(t, |u| [1.0, u, u * u, u * u * u])
+    }
+    fn velocity(&self, t: f32) -> glm::Vec3 {
+        self.evaluate(t, |u| [0.0, 1.0, 2.0 * u, 3.0 * u * u])
+    }
+    fn acceleration(&self, t: f32) -> glm::Vec3 {
+        self.evaluate(t, |u| [0.0, 0.0, 2.0, 6.0 * u])
+    }
+}
+
+// tilts the rider into curves like a train on a canted track, the angle is
+// the one at which gravity and the centripetal force add up to straight down
+#[derive(Clone, Copy, Debug)]
+pub struct Banking {
+    pub gravity: f32,
+    pub max_angle: f32,
+}
+
+impl Banking {
+    pub fn new(gravity: f32, max_angle: f32) -> Self {
+        Self { gravity, max_angle }
+    }
+
+    pub fn angle(&self, route: &dyn Route, t: f32, right: &glm::Vec3) -> f32 {
+        let v = route.velocity(t);
+        let a = route.acceleration(t);
+        let speed2 = glm::dot(&v, &v);
+        if speed2 < 1e-9 { return 0.0 }
+        // the sideways part of the acceleration, positive when turning right
+        let tangent = v / speed2.sqrt();
+        let lateral = glm::dot(&(a - tangent * glm::dot(&a, &tangent)), right);
+        (lateral / self.gravity).atan().max(-self.max_angle).min(self.max_angle)
+    }
+}
+
+impl Default for Banking {
+    fn default() -> Self { Self::new(9.81, crate::QUARTER_PI) }
+}
+
+// follows a route with an up vector that is carried along by rotation minimizing
+// frames (double reflection), so it never flips where the Frenet normal would;
+// its frames have the up vector as normal and the right vector as binormal
+pub struct RouteRider {
+    route: Rc<dyn Route>,
+    t: f32,
+    position: glm::Vec3,
+    tangent: glm::Vec3,
+    up: glm::Vec3,
+    // the largest step in t between two propagated frames
+    pub max_step: f32,
+    pub banking: Option<Banking>,
+}
+
+impl RouteRider {
+
+    // the up vector is made perpendicular to the route at t
+    pub fn new(route: Rc<dyn Route>, t: f32, up: glm::Vec3) -> Self {
+        let position = route.position(t);
+        let mut tangent = route.tangent(t);
+        if glm::length(&tangent) == 0.0 { tangent = glm::vec3(0.0, 0.0, 1.0) }
+        let mut up = normalize_or_zero(&(up - tangent * glm::dot(&up, &tangent)));
+        if glm::length(&up) == 0.0 { up = perpendicular(&tangent) }
+        Self { route, t, position, tangent, up, max_step: 0.01, banking: None }
+    }
+
+    pub fn get_route(&self) -> Rc<dyn Route> { self.route.clone() }
+    pub fn get_t(&self) -> f32 { self.t }
+
+    // walks to t in small steps, backwards works too
+    pub fn advance(&mut self, t: f32) -> RouteFrame {
+        let n_steps = ((t - self.t).abs() / self.max_step).ceil().max(1.0) as usize;
+        let step = (t - self.t) / n_steps as f32;
+        for i in 1..=n_steps {
+            let next_t = if i == n_steps { t } else { self.t + step };
+            self.step_to(next_t);
+        }
+        self.frame()
+    }
+
+    fn step_to(&mut self, t: f32) {
+        let position = self.route.position(t);
+        let mut tangent = self.route.tangent(t);
+        // keep the old direction where the route stands still
+        if glm::length(&tangent) == 0.0 { tangent = self.tangent }
+
+        // reflect the frame in the plane between the two positions, then in the
+        // plane between the reflected and the new tangent
+        let v1 = position - self.position;
+        let c1 = glm::dot(&v1, &v1);
+        let mut up = self.up;
+        let mut reflected_tangent = self.tangent;
+        if c1 > 1e-12 {
+            up -= v1 * (2.0 / c1 * glm::dot(&v1, &up));
+            reflected_tangent -= v1 * (2.0 / c1 * glm::dot(&v1, &reflected_tangent));
+        }
+        let v2 = tangent - reflected_tangent;
+        let c2 = glm::dot(&v2, &v2);
+        if c2 > 1e-12 {
+            up -= v2 * (2.0 / c2 * glm::dot(&v2, &up));
+        }
+
+        // take out the drift
+        up = normalize_or_zero(&(up - tangent * glm::dot(&up, &tangent)));
+        if glm::length(&up) == 0.0 { up = perpendicular(&tangent) }
+
+        self.t = t;
+        self.position = position;
+        self.tangent = tangent;
+        self.up = up;
+    }
+
+    // without banking
+    pub fn unbanked_frame(&self) -> RouteFrame {
+        RouteFrame {
+            position: self.position,
+            tangent: self.tangent,
+            normal: self.up,
+            binormal: glm::cross(&self.tangent, &self.up),
+        }
+    }
+
+    pub fn frame(&self) -> RouteFrame {
+        let frame = self.unbanked_frame();
+        let angle = match &self.banking {
+            Some(banking) => banking.angle(&*self.route, self.t, &frame.binormal),
+            None => return frame,
+        };
+        // roll around the tangent, leaning into the curve
+        let up = frame.normal * angle.cos() + frame.binormal * angle.sin();
+        RouteFrame {
+            normal: up,
+            binormal: glm::cross(&frame.tangent, &up),
+            ..frame
+        }
+    }
+}
+
+fn normalize_or_zero(v: &glm::Vec3) -> glm::Vec3 {
+    let len = glm::length(v);
+    if len > 1e-9 { v / len } else { glm::zero() }
+}
+
+// any unit vector perpendicular to v, preferring the one closest to up
+pub(crate) fn perpendicular(v: &glm::Vec3) -> glm::Vec3 {
+    let axis = if v.y.abs() < 0.99 { glm::Vec3::y() } else { glm::Vec3::x() };
+    normalize_or_zero(&(axis - v * glm::dot(&axis, v)))
+}
@@ -0,0 +1,126 @@
+use crate::{ MyContext, Key };
+
+// playback control over the movie time, the MoviePlayer carries out what is asked
+// here at the start of the next frame
+pub struct Transport {
+    pub paused: bool,
+    // multiplies the movie time a frame covers
+    pub speed: f64,
+    // plays from start to end over and over
+    pub loop_range: Option<(f64, f64)>,
+    loop_in: Option<f64>,
+    step_frames: usize,
+    seek_to: Option<f64>,
+}
+
+impl Transport {
+
+    pub fn ne() -> Self {
+        Self {
+            paused: false,
+            speed: 1.0,
+            loop_range: None,
+            loop_in: None,
+            step_frames: 0,
+            seek_to: None,
+        }
+    }
+
+    pub fn pause(&mut self) { self.paused = true; }
+    pub fn resume(&mut self) { self.paused = false; }
+    pub fn toggle_pause(&mut self) { self.paused = !self.paused; }
+
+    // advances one update and stays paused
+    pub fn step_frame(&mut self) {
+        self.paused = true;
+        self.step_frames += 1;
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.max(0.0);
+    }
+
+    pub fn seek(&mut self, t: f64) {
+        self.seek_to = Some(t.max(0.0));
+    }
+
+    pub fn set_loop(&mut self, start: f64, end: f64) {
+        if end <= start {
+            println!("A loop range has to end after it starts");
+            return
+        }
+        self.loop_range = Some((start, end));
+    }
+
+    pub fn clear_loop(&mut self) {
+        self.loop_range = None;
+        self.loop_in = None;
+    }
+
+    // the MoviePlayer asks these once per frame
+
+    pub(crate) fn take_seek(&mut self) -> Option<f64> { self.seek_to.take() }
+
+    // whether the movie time moves this frame
+    pub(crate) fn take_advance(&mut self) -> bool {
+        if !self.paused { return true }
+        if self.step_frames > 0 {
+            self.step_frames -= 1;
+            return true
+        }
+        false
+    }
+
+    // the start of the loop once t has passed its end
+    pub(crate) fn loop_back(&self, t: f64) -> Option<f64> {
+        match self.loop_range {
+            Some((start, end)) if t >= end => Some(start),
+            _ => None,
+        }
+    }
+
+    // the debug bindings:
+    // P pause, . step, , step back, [ and ] halve and double the speed, \ normal speed,
+    // - and = seek 5 seconds, Home restart, I and O set the loop, L clears it
+    pub fn handle_keys(ctx: &mut MyContext) {
+        let t = ctx.time();
+        let dt = ctx.frame_time();
+        let pressed = |k: Key| ctx.is_key_pressed_this_frame(k);
+        let (pause, step, back) = (pressed(Key::P), pressed(Key::Period), pressed(Key::Comma));
+        let (slower, faster, normal) = (pressed(Key::LeftBracket), pressed(Key::RightBracket), pressed(Key::Backslash));
+        let (rewind, forward, restart) = (pressed(Key::Minus), pressed(Key::Equal), pressed(Key::Home));
+        let (loop_in, loop_out, no_loop) = (pressed(Key::I), pressed(Key::O), pressed(Key::L));
+
+        let transport = &mut ctx.transport;
+        if pause {
+            transport.toggle_pause();
+            println!("{} at {:.2}s", if transport.paused { "Paused" } else { "Playing" }, t);
+        }
+        if step { transport.step_frame() }
+        if back {
+            transport.paused = true;
+            transport.seek(t - dt);
+        }
+        if slower || faster || normal {
+            let speed = if normal { 1.0 } else if faster { transport.speed * 2.0 } else { transport.speed / 2.0 };
+            transport.set_speed(speed);
+            println!("Speed {}x", transport.speed);
+        }
+        if rewind { transport.seek(t - 5.0) }
+        if forward { transport.seek(t + 5.0) }
+        if restart { transport.seek(0.0) }
+        if loop_in {
+            transport.loop_in = Some(t);
+            println!("Loop from {:.2}s", t);
+        }
+        if loop_out {
+            let start = transport.loop_in.unwrap_or(0.0);
+            transport.set_loop(start, t);
+            println!("Looping {:.2}s to {:.2}s", start, t);
+        }
+        if no_loop {
+            transport.clear_loop();
+            println!("Stopped looping");
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{ Vertex, Type };
+
+use nalgebra_glm as glm;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: glm::Vec3,
+    pub max: glm::Vec3,
+}
+
+impl Aabb {
+
+    pub fn center(&self) -> glm::Vec3 { (self.min + self.max) * 0.5 }
+    pub fn size(&self) -> glm::Vec3 { self.max - self.min }
+
+    pub fn corners(&self) -> [glm::Vec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            glm::vec3(a.x, a.y, a.z), glm::vec3(b.x, a.y, a.z),
+            glm::vec3(a.x, b.y, a.z), glm::vec3(b.x, b.y, a.z),
+            glm::vec3(a.x, a.y, b.z), glm::vec3(b.x, a.y, b.z),
+            glm::vec3(a.x, b.y, b.z), glm::vec3(b.x, b.y, b.z),
+        ]
+    }
+
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Aabb { min: glm::min2(&self.min, &other.min), max: glm::max2(&self.max, &other.max) }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BoundingSphere {
+    pub center: glm::Vec3,
+    pub radius: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    pub aabb: Aabb,
+    pub sphere: BoundingSphere,
+}
+
+impl Bounds {
+
+    // the sphere is centered on the box, which is not the tightest but cheap
+    pub fn from_points<I: IntoIterator<Item = glm::Vec3>>(points: I) -> Option<Self> {
+        let points: Vec<glm::Vec3> = points.into_iter().collect();
+        let first = *points.first()?;
+        let mut aabb = Aabb { min: first, max: first };
+        for p in points.iter() {
+            aabb.min = glm::min2(&aabb.min, p);
+            aabb.max = glm::max2(&aabb.max, p);
+        }
+        let center = aabb.center();
+        let radius = points.iter().fold(0.0f32, |r, p| r.max(glm::distance(p, &center)));
+        Some(Self { aabb, sphere: BoundingSphere { center, radius } })
+    }
+
+    // reads location 0 of every vertex, which is the position for all vertices in
+    // peppaint, returns None when that attribute is not made of floats
+    pub fn from_vertices<T: Vertex>(vertices: &[T]) -> Option<Self> {
+        let layout = T::get_layout();
+        let position = layout.attrib_layouts.iter().find(|al| al.location == 0)?;
+        match position.type_ {
+            Type::Float => {},
+            _ => return None
+        }
+        let stride = layout.stride;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                vertices.as_ptr() as *const u8,
+                std::mem::size_of_val(vertices)
+            )
+        };
+        let read = |at: usize| {
+            let mut b = [0u8; 4];
+            b.copy_from_slice(&bytes[at..at + 4]);
+            f32::from_ne_bytes(b)
+        };
+        let n_elements = position.n_elements.min(3);
+        let points = (0..vertices.len()).map(|i| {
+            let start = i * stride + position.byte_offset;
+            let mut p = glm::vec3(0.0, 0.0, 0.0);
+            for e in 0..n_elements { p[e] = read(start + 4 * e); }
+            p
+        });
+        Self::from_points(points)
+    }
+
+    pub fn merge(&self, other: &Bounds) -> Bounds {
+        let aabb = self.aabb.merge(&other.aabb);
+        let center = aabb.center();
+        let reach = |s: &BoundingSphere| glm::distance(&s.center, &center) + s.radius;
+        let radius = reach(&self.sphere).max(reach(&other.sphere));
+        Bounds { aabb, sphere: BoundingSphere { center, radius } }
+    }
+
+    pub fn transformed(&self, m: &glm::Mat4) -> Bounds {
+        let transform = |p: &glm::Vec3| {
+            let p = m * glm::vec4(p.x, p.y, p.z, 1.0);
+            glm::vec3(p.x, p.y, p.z)
+        };
+        let corners = self.aabb.corners();
+        let first = transform(&corners[0]);
+        let mut aabb = Aabb { min: first, max: first };
+        for c in corners.iter().skip(1) {
+            let c = transform(c);
+            aabb.min = glm::min2(&aabb.min, &c);
+            aabb.max = glm::max2(&aabb.max, &c);
+        }
+        let scale = (0..3)
+            .map(|i| glm::length(&glm::vec3(m[(0, i)], m[(1, i)], m[(2, i)])))
+            .fold(0.0f32, f32::max);
+        let sphere = BoundingSphere {
+            center: transform(&self.sphere.center),
+            radius: self.sphere.radius * scale,
+        };
+        Bounds { aabb, sphere }
+    }
+}
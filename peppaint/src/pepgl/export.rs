@@ -0,0 +1,271 @@
+use crate::{
+    Mesh,
+    Vertex,
+};
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{ Write, BufWriter };
+use std::path::Path;
+
+// what an exporter can read from a vertex, only the position is required
+pub trait ExportVertex {
+    fn position(&self) -> [f32; 3];
+    fn normal(&self) -> Option<[f32; 3]> { None }
+    fn color(&self) -> Option<[f32; 4]> { None }
+    fn texcoords(&self) -> Option<[f32; 2]> { None }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian
+}
+
+impl<T: Vertex + ExportVertex + Copy + Debug> Mesh<T> {
+
+    pub fn to_obj(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Could not create .obj file: {}", e))?;
+        self.write_obj(&mut BufWriter::new(file))
+            .map_err(|e| format!("Could not write .obj file: {}", e))
+    }
+
+    pub fn to_ply(&self, path: &Path, format: PlyFormat) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Could not create .ply file: {}", e))?;
+        self.write_ply(&mut BufWriter::new(file), format)
+            .map_err(|e| format!("Could not write .ply file: {}", e))
+    }
+
+    pub fn write_obj<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        write_obj(w, self.get_vertices(), self.get_indices())
+    }
+
+    pub fn write_ply<W: Write>(&self, w: &mut W, format: PlyFormat) -> std::io::Result<()> {
+        write_ply(w, self.get_vertices(), self.get_indices(), format)
+    }
+}
+
+// vertex colors are written after the position, which most tools (Blender, MeshLab) read
+pub fn write_obj<T: ExportVertex, W: Write>(w: &mut W, vertices: &[T], indices: &[u32]) -> std::io::Result<()> {
+    let (has_normals, has_colors, has_texcoords) = attributes_present(vertices);
+
+    writeln!(w, "# exported by peppaint")?;
+    for v in vertices {
+        let p = v.position();
+        match v.color() {
+            Some(c) if has_colors => writeln!(w, "v {} {} {} {} {} {}", p[0], p[1], p[2], c[0], c[1], c[2])?,
+            _ => writeln!(w, "v {} {} {}", p[0], p[1], p[2])?
+        }
+    }
+    if has_texcoords {
+        for v in vertices {
+            let t = v.texcoords().unwrap();
+            writeln!(w, "vt {} {}", t[0], t[1])?;
+        }
+    }
+    if has_normals {
+        for v in vertices {
+            let n = v.normal().unwrap();
+            writeln!(w, "vn {} {} {}", n[0], n[1], n[2])?;
+        }
+    }
+
+    // obj indices start at 1, every attribute shares the vertex index
+    for tri in indices.chunks(3) {
+        if tri.len() < 3 { break }
+        write!(w, "f")?;
+        for i in tri {
+            let i = i + 1;
+            match (has_texcoords, has_normals) {
+                (true, true) => write!(w, " {}/{}/{}", i, i, i)?,
+                (true, false) => write!(w, " {}/{}", i, i)?,
+                (false, true) => write!(w, " {}//{}", i, i)?,
+                (false, false) => write!(w, " {}", i)?,
+            }
+        }
+        writeln!(w)?;
+    }
+    w.flush()
+}
+
+pub fn write_ply<T: ExportVertex, W: Write>(
+    w: &mut W, 
+    vertices: &[T], 
+    indices: &[u32], 
+    format: PlyFormat
+) -> std::io::Result<()> {
+    let (has_normals, has_colors, has_texcoords) = attributes_present(vertices);
+
+    writeln!(w, "ply")?;
+    match format {
+        PlyFormat::Ascii => writeln!(w, "format ascii 1.0")?,
+        PlyFormat::BinaryLittleEndian => writeln!(w, "format binary_little_endian 1.0")?,
+    }
+    writeln!(w, "comment exported by peppaint")?;
+    writeln!(w, "element vertex {}", vertices.len())?;
+    writeln!(w, "property float x\nproperty float y\nproperty float z")?;
+    if has_normals {
+        writeln!(w, "property float nx\nproperty float ny\nproperty float nz")?;
+    }
+    if has_colors {
+        writeln!(w, "property uchar red\nproperty uchar green\nproperty uchar blue\nproperty uchar alpha")?;
+    }
+    if has_texcoords {
+        writeln!(w, "property float s\nproperty float t")?;
+    }
+    writeln!(w, "element face {}", indices.len() / 3)?;
+    writeln!(w, "property list uchar uint vertex_indices")?;
+    writeln!(w, "end_header")?;
+
+    for v in vertices {
+        let mut floats = Vec::with_capacity(8);
+        floats.extend_from_slice(&v.position());
+        if has_normals { floats.extend_from_slice(&v.normal().unwrap()); }
+        let mut colors = [0u8; 4];
+        if has_colors {
+            let c = v.color().unwrap();
+            for i in 0..4 {
+                colors[i] = (c[i].clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+        let texcoords = v.texcoords();
+
+        match format {
+            PlyFormat::Ascii => {
+                let mut line: Vec<String> = floats.iter().map(|f| f.to_string()).collect();
+                if has_colors { line.extend(colors.iter().map(|c| c.to_string())); }
+                if has_texcoords { line.extend(texcoords.unwrap().iter().map(|f| f.to_string())); }
+                writeln!(w, "{}", line.join(" "))?;
+            },
+            PlyFormat::BinaryLittleEndian => {
+                for f in floats.iter() { w.write_all(&f.to_le_bytes())?; }
+                if has_colors { w.write_all(&colors)?; }
+                if has_texcoords {
+                    for f in texcoords.unwrap().iter() { w.write_all(&f.to_le_bytes())?; }
+                }
+            }
+        }
+    }
+
+    for tri in indices.chunks(3) {
+        if tri.len() < 3 { break }
+        match format {
+            PlyFormat::Ascii => writeln!(w, "3 {} {} {}", tri[0], tri[1], tri[2])?,
+            PlyFormat::BinaryLittleEndian => {
+                w.write_all(&[3u8])?;
+                for i in tri { w.write_all(&i.to_le_bytes())?; }
+            }
+        }
+    }
+    w.flush()
+}
+
+// an attribute is only exported when every vertex has it
+fn attributes_present<T: ExportVertex>(vertices: &[T]) -> (bool, bool, bool) {
+    let all = |f: &dyn Fn(&T) -> bool| !vertices.is_empty() && vertices.iter().all(f);
+    (
+        all(&|v| v.normal().is_some()),
+        all(&|v| v.color().is_some()),
+        all(&|v| v.texcoords().is_some()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::std::PosNormTex;
+    use crate::types::{ Vec2, Vec3 };
+
+    // a quad and a triangle above it, the faces use the vertices in order so the
+    // indices tobj makes are the same as ours
+    fn data() -> (Vec<PosNormTex>, Vec<u32>) {
+        let v = |p: [f32; 3], n: [f32; 3], t: [f32; 2]| PosNormTex {
+            pos: Vec3(p[0], p[1], p[2]),
+            normal: Vec3(n[0], n[1], n[2]),
+            texcoords: Vec2(t[0], t[1]),
+        };
+        let vertices = vec![
+            v([0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0]),
+            v([1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0]),
+            v([1.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 1.0]),
+            v([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0]),
+            v([0.25, 0.5, 2.0], [0.0, 0.6, 0.8], [0.125, 0.75]),
+        ];
+        let indices = vec![0, 1, 2, 0, 2, 3, 3, 2, 4];
+        (vertices, indices)
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("peppaint_{}_{}", std::process::id(), name))
+    }
+
+    fn header(bytes: &[u8]) -> (String, usize) {
+        let end = b"end_header\n";
+        let at = bytes.windows(end.len()).position(|w| w == end).expect("no end_header") + end.len();
+        (String::from_utf8(bytes[..at].to_vec()).unwrap(), at)
+    }
+
+    fn element_count(header: &str, element: &str) -> usize {
+        let prefix = format!("element {} ", element);
+        let line = header.lines().find(|l| l.starts_with(&prefix)).expect("no element line");
+        line[prefix.len()..].parse().unwrap()
+    }
+
+    #[test]
+    fn obj_round_trip() {
+        let (vertices, indices) = data();
+        let path = temp_path("round_trip.obj");
+        let file = File::create(&path).unwrap();
+        write_obj(&mut BufWriter::new(file), &vertices, &indices).unwrap();
+
+        // the same loader Model::dot_obj uses
+        let (models, _) = tobj::load_obj(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(models.len(), 1);
+        let mesh = &models[0].mesh;
+
+        let positions: Vec<f32> = vertices.iter().flat_map(|v| v.position().to_vec()).collect();
+        let normals: Vec<f32> = vertices.iter().flat_map(|v| v.normal().unwrap().to_vec()).collect();
+        let texcoords: Vec<f32> = vertices.iter().flat_map(|v| v.texcoords().unwrap().to_vec()).collect();
+        assert_eq!(mesh.positions, positions);
+        assert_eq!(mesh.normals, normals);
+        assert_eq!(mesh.texcoords, texcoords);
+        assert_eq!(mesh.indices, indices);
+    }
+
+    #[test]
+    fn ascii_ply() {
+        let (vertices, indices) = data();
+        let mut bytes = Vec::new();
+        write_ply(&mut bytes, &vertices, &indices, PlyFormat::Ascii).unwrap();
+        let (header, at) = header(&bytes);
+        assert!(header.contains("format ascii 1.0"));
+        assert_eq!(element_count(&header, "vertex"), vertices.len());
+        assert_eq!(element_count(&header, "face"), indices.len() / 3);
+
+        let body = String::from_utf8(bytes[at..].to_vec()).unwrap();
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), vertices.len() + indices.len() / 3);
+        // position, normal and texcoords
+        assert!(lines[..vertices.len()].iter().all(|l| l.split(' ').count() == 8));
+        assert_eq!(lines[vertices.len()], "3 0 1 2");
+    }
+
+    #[test]
+    fn binary_ply() {
+        let (vertices, indices) = data();
+        let mut bytes = Vec::new();
+        write_ply(&mut bytes, &vertices, &indices, PlyFormat::BinaryLittleEndian).unwrap();
+        let (header, at) = header(&bytes);
+        assert!(header.contains("format binary_little_endian 1.0"));
+        assert_eq!(element_count(&header, "vertex"), vertices.len());
+        assert_eq!(element_count(&header, "face"), indices.len() / 3);
+
+        // 8 floats per vertex, a count byte and 3 uints per face
+        let expected = vertices.len() * 8 * 4 + indices.len() / 3 * (1 + 3 * 4);
+        assert_eq!(bytes.len() - at, expected);
+        let second = at + 8 * 4;
+        let x = f32::from_le_bytes([bytes[second], bytes[second + 1], bytes[second + 2], bytes[second + 3]]);
+        assert_eq!(x, vertices[1].position()[0]);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{ Texture, Uint, Int };
+use gl;
+
+pub struct Framebuffer {
+    id: Uint,
+    width: usize,
+    height: usize,
+    color: Option<Texture>,
+    depth: Option<Texture>,
+}
+
+impl Framebuffer {
+
+    // only a depth attachment, used for shadow maps
+    pub fn depth_only(width: usize, height: usize) -> Self {
+        let depth = Texture::depth(width, height);
+        unsafe {
+            let mut id = 0;
+            gl::GenFramebuffers(1, &mut id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, id);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER, 
+                gl::DEPTH_ATTACHMENT, 
+                gl::TEXTURE_2D, 
+                depth.get_id(), 
+                0
+            );
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                println!("Depth framebuffer of {}x{} is not complete", width, height);
+            }
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            Self { id, width, height, color: None, depth: Some(depth) }
+        }
+    }
+
+    // a color and a depth attachment, to render a whole scene offscreen
+    pub fn with_color(width: usize, height: usize) -> Self {
+        let color = Texture::color(width, height);
+        let depth = Texture::depth(width, height);
+        unsafe {
+            let mut id = 0;
+            gl::GenFramebuffers(1, &mut id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, id);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER, 
+                gl::COLOR_ATTACHMENT0, 
+                gl::TEXTURE_2D, 
+                color.get_id(), 
+                0
+            );
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER, 
+                gl::DEPTH_ATTACHMENT, 
+                gl::TEXTURE_2D, 
+                depth.get_id(), 
+                0
+            );
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                println!("Color framebuffer of {}x{} is not complete", width, height);
+            }
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            Self { id, width, height, color: Some(color), depth: Some(depth) }
+        }
+    }
+
+    pub fn clear(&self) {
+        self.bind();
+        unsafe { gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT); }
+    }
+
+    // binds the framebuffer and sets the viewport to its size
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+            gl::Viewport(0, 0, self.width as Int, self.height as Int);
+        }
+    }
+
+    pub fn bind_default() {
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0); }
+    }
+
+    pub fn get_width(&self) -> usize { self.width }
+    pub fn get_height(&self) -> usize { self.height }
+    pub fn get_color(&self) -> Option<&Texture> { self.color.as_ref() }
+    pub fn get_depth(&self) -> Option<&Texture> { self.depth.as_ref() }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteFramebuffers(1, &self.id) }
+    }
+}
+
+pub fn gl_get_viewport() -> [Int; 4] {
+    let mut viewport = [0; 4];
+    unsafe { gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr()); }
+    viewport
+}
+
+pub fn gl_set_viewport(viewport: [Int; 4]) {
+    unsafe { gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]); }
+}
@@ -0,0 +1,268 @@
+use glfw::ffi::*;
+use std::os::raw::*;
+use std::ffi::CStr;
+use crate::Event;
+
+// the buttons of the standard (Xbox like) layout GLFW maps gamepads to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+impl GamepadButton {
+    pub fn to_glfw(&self) -> c_int {
+        use GamepadButton::*;
+        match self {
+            A => GAMEPAD_BUTTON_A,
+            B => GAMEPAD_BUTTON_B,
+            X => GAMEPAD_BUTTON_X,
+            Y => GAMEPAD_BUTTON_Y,
+            LeftBumper => GAMEPAD_BUTTON_LEFT_BUMPER,
+            RightBumper => GAMEPAD_BUTTON_RIGHT_BUMPER,
+            Back => GAMEPAD_BUTTON_BACK,
+            Start => GAMEPAD_BUTTON_START,
+            Guide => GAMEPAD_BUTTON_GUIDE,
+            LeftThumb => GAMEPAD_BUTTON_LEFT_THUMB,
+            RightThumb => GAMEPAD_BUTTON_RIGHT_THUMB,
+            DpadUp => GAMEPAD_BUTTON_DPAD_UP,
+            DpadRight => GAMEPAD_BUTTON_DPAD_RIGHT,
+            DpadDown => GAMEPAD_BUTTON_DPAD_DOWN,
+            DpadLeft => GAMEPAD_BUTTON_DPAD_LEFT,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    // from 0 released to 1 fully pressed
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub fn to_glfw(&self) -> c_int {
+        use GamepadAxis::*;
+        match self {
+            LeftX => GAMEPAD_AXIS_LEFT_X,
+            LeftY => GAMEPAD_AXIS_LEFT_Y,
+            RightX => GAMEPAD_AXIS_RIGHT_X,
+            RightY => GAMEPAD_AXIS_RIGHT_Y,
+            LeftTrigger => GAMEPAD_AXIS_LEFT_TRIGGER,
+            RightTrigger => GAMEPAD_AXIS_RIGHT_TRIGGER,
+        }
+    }
+}
+
+const N_BUTTONS: usize = (GAMEPAD_BUTTON_LAST + 1) as usize;
+const N_AXES: usize = (GAMEPAD_AXIS_LAST + 1) as usize;
+
+// the state of one connected device, polled once per frame; devices without a
+// gamepad mapping only have raw axes and buttons
+pub struct Joystick {
+    pub id: i32,
+    pub name: String,
+    pub guid: String,
+    pub is_gamepad: bool,
+    pub axes: Vec<f32>,
+    pub buttons: Vec<bool>,
+    pub hats: Vec<u8>,
+    gamepad_buttons: [bool; N_BUTTONS],
+    last_gamepad_buttons: [bool; N_BUTTONS],
+    // deadzone applied
+    gamepad_axes: [f32; N_AXES],
+}
+
+impl Joystick {
+
+    fn connect(id: i32) -> Self {
+        unsafe {
+            let is_gamepad = glfwJoystickIsGamepad(id) == TRUE;
+            let name = if is_gamepad { glfwGetGamepadName(id) } else { glfwGetJoystickName(id) };
+            Self {
+                id,
+                name: c_string(name),
+                guid: c_string(glfwGetJoystickGUID(id)),
+                is_gamepad,
+                axes: vec![],
+                buttons: vec![],
+                hats: vec![],
+                gamepad_buttons: [false; N_BUTTONS],
+                last_gamepad_buttons: [false; N_BUTTONS],
+                gamepad_axes: [0.0; N_AXES],
+            }
+        }
+    }
+
+    fn poll(&mut self, deadzone: f32) {
+        unsafe {
+            let mut count: c_int = 0;
+            let axes = glfwGetJoystickAxes(self.id, &mut count);
+            self.axes = read_array(axes, count).iter().map(|a| apply_deadzone(*a, deadzone)).collect();
+            let buttons = glfwGetJoystickButtons(self.id, &mut count);
+            self.buttons = read_array(buttons, count).iter().map(|b| *b as c_int == PRESS).collect();
+            let hats = glfwGetJoystickHats(self.id, &mut count);
+            self.hats = read_array(hats, count).to_vec();
+
+            self.last_gamepad_buttons = self.gamepad_buttons;
+            if !self.is_gamepad { return }
+            let mut state = GLFWgamepadstate { buttons: [0; N_BUTTONS], axes: [0.0; N_AXES] };
+            if glfwGetGamepadState(self.id, &mut state) == FALSE { return }
+            for i in 0..N_BUTTONS {
+                self.gamepad_buttons[i] = state.buttons[i] as c_int == PRESS;
+            }
+            let (lx, ly) = apply_radial_deadzone(state.axes[0], state.axes[1], deadzone);
+            let (rx, ry) = apply_radial_deadzone(state.axes[2], state.axes[3], deadzone);
+            // triggers rest at -1
+            let lt = apply_deadzone((state.axes[4] + 1.0) / 2.0, deadzone);
+            let rt = apply_deadzone((state.axes[5] + 1.0) / 2.0, deadzone);
+            self.gamepad_axes = [lx, ly, rx, ry, lt, rt];
+        }
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons[button.to_glfw() as usize]
+    }
+
+    pub fn is_pressed_this_frame(&self, button: GamepadButton) -> bool {
+        let i = button.to_glfw() as usize;
+        self.gamepad_buttons[i] && !self.last_gamepad_buttons[i]
+    }
+
+    pub fn is_released_this_frame(&self, button: GamepadButton) -> bool {
+        let i = button.to_glfw() as usize;
+        !self.gamepad_buttons[i] && self.last_gamepad_buttons[i]
+    }
+
+    pub fn get_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes[axis.to_glfw() as usize]
+    }
+
+    pub fn get_left_stick(&self) -> (f32, f32) {
+        (self.get_axis(GamepadAxis::LeftX), self.get_axis(GamepadAxis::LeftY))
+    }
+
+    pub fn get_right_stick(&self) -> (f32, f32) {
+        (self.get_axis(GamepadAxis::RightX), self.get_axis(GamepadAxis::RightY))
+    }
+}
+
+// all connected joysticks, in order of their id
+pub struct Gamepads {
+    devices: Vec<Joystick>,
+    // axis values below this count as 0, the rest is rescaled to start at 0;
+    // used up to 0.99
+    pub deadzone: f32,
+}
+
+impl Gamepads {
+
+    pub fn ne() -> Self {
+        Self { devices: vec![], deadzone: 0.15 }
+    }
+
+    // connections are found by polling, so no callback is needed; returns the
+    // connect and disconnect events
+    pub fn update(&mut self) -> Vec<Event> {
+        let mut events = vec![];
+        for id in JOYSTICK_1..=JOYSTICK_LAST {
+            let present = unsafe { glfwJoystickPresent(id) == TRUE };
+            let index = self.devices.iter().position(|d| d.id == id);
+            match (present, index) {
+                (true, None) => {
+                    let device = Joystick::connect(id);
+                    println!("Connected {} ({})", device.name, id);
+                    self.devices.push(device);
+                    events.push(Event::JoystickConnected(id));
+                }
+                (false, Some(i)) => {
+                    let device = self.devices.remove(i);
+                    println!("Disconnected {} ({})", device.name, id);
+                    events.push(Event::JoystickDisconnected(id));
+                }
+                _ => {}
+            }
+        }
+        self.devices.sort_by_key(|d| d.id);
+        // a deadzone of 1 would rescale by 0 / 0
+        let deadzone = self.deadzone.clamp(0.0, 0.99);
+        for device in &mut self.devices {
+            device.poll(deadzone);
+        }
+        events
+    }
+
+    pub fn get_devices(&self) -> &[Joystick] { &self.devices }
+
+    pub fn get(&self, id: i32) -> Option<&Joystick> {
+        self.devices.iter().find(|d| d.id == id)
+    }
+
+    // the first device with a gamepad mapping
+    pub fn first(&self) -> Option<&Joystick> {
+        self.devices.iter().find(|d| d.is_gamepad)
+    }
+
+    // on any gamepad
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.devices.iter().any(|d| d.is_pressed(button))
+    }
+
+    pub fn is_pressed_this_frame(&self, button: GamepadButton) -> bool {
+        self.devices.iter().any(|d| d.is_pressed_this_frame(button))
+    }
+
+    // of the first gamepad, 0 without one
+    pub fn get_axis(&self, axis: GamepadAxis) -> f32 {
+        self.first().map(|d| d.get_axis(axis)).unwrap_or(0.0)
+    }
+
+    // takes an SDL_GameControllerDB string for devices GLFW does not know
+    pub fn add_mappings(&mut self, mappings: &str) -> Result<(), String> {
+        let ok = unsafe { glfwUpdateGamepadMappings(const_char_ptr!(mappings)) == TRUE };
+        if !ok { return Err("Could not parse the gamepad mappings".to_string()) }
+        // devices already connected may have become gamepads
+        for device in &mut self.devices {
+            *device = Joystick::connect(device.id);
+        }
+        Ok(())
+    }
+}
+
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value.abs() < deadzone { return 0.0 }
+    value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+}
+
+// on the length of the stick, so diagonals are not snapped to the axes
+fn apply_radial_deadzone(x: f32, y: f32, deadzone: f32) -> (f32, f32) {
+    let length = (x * x + y * y).sqrt();
+    if length < deadzone { return (0.0, 0.0) }
+    let scale = ((length - deadzone) / (1.0 - deadzone)).min(1.0) / length;
+    (x * scale, y * scale)
+}
+
+unsafe fn read_array<'a, T>(ptr: *const T, count: c_int) -> &'a [T] {
+    if ptr.is_null() || count <= 0 { &[] } else { std::slice::from_raw_parts(ptr, count as usize) }
+}
+
+unsafe fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() { String::new() } else { CStr::from_ptr(ptr).to_string_lossy().into_owned() }
+}
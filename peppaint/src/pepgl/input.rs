@@ -0,0 +1,450 @@
+use glfw::ffi::*;
+use std::os::raw::*;
+use std::collections::HashSet;
+
+macro_rules! glfw_keys {
+    ($($key:ident => $code:ident),* $(,)?) => {
+        // every key GLFW knows, named after the US layout
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Key {
+            $($key),*
+        }
+
+        impl Key {
+            pub fn to_glfw(&self) -> c_int {
+                match self {
+                    $(Key::$key => $code),*
+                }
+            }
+
+            pub fn from_glfw(code: c_int) -> Self {
+                match code {
+                    $($code => Key::$key,)*
+                    _ => Key::Unknown,
+                }
+            }
+
+            pub fn all() -> &'static [Key] {
+                &[$(Key::$key),*]
+            }
+        }
+    };
+}
+
+glfw_keys! {
+    Unknown => KEY_UNKNOWN,
+    Space => KEY_SPACE,
+    Apostrophe => KEY_APOSTROPHE,
+    Comma => KEY_COMMA,
+    Minus => KEY_MINUS,
+    Period => KEY_PERIOD,
+    Slash => KEY_SLASH,
+    Num0 => KEY_0,
+    Num1 => KEY_1,
+    Num2 => KEY_2,
+    Num3 => KEY_3,
+    Num4 => KEY_4,
+    Num5 => KEY_5,
+    Num6 => KEY_6,
+    Num7 => KEY_7,
+    Num8 => KEY_8,
+    Num9 => KEY_9,
+    Semicolon => KEY_SEMICOLON,
+    Equal => KEY_EQUAL,
+    A => KEY_A,
+    B => KEY_B,
+    C => KEY_C,
+    D => KEY_D,
+    E => KEY_E,
+    F => KEY_F,
+    G => KEY_G,
+    H => KEY_H,
+    I => KEY_I,
+    J => KEY_J,
+    K => KEY_K,
+    L => KEY_L,
+    M => KEY_M,
+    N => KEY_N,
+    O => KEY_O,
+    P => KEY_P,
+    Q => KEY_Q,
+    R => KEY_R,
+    S => KEY_S,
+    T => KEY_T,
+    U => KEY_U,
+    V => KEY_V,
+    W => KEY_W,
+    X => KEY_X,
+    Y => KEY_Y,
+    Z => KEY_Z,
+    LeftBracket => KEY_LEFT_BRACKET,
+    Backslash => KEY_BACKSLASH,
+    RightBracket => KEY_RIGHT_BRACKET,
+    GraveAccent => KEY_GRAVE_ACCENT,
+    World1 => KEY_WORLD_1,
+    World2 => KEY_WORLD_2,
+    Escape => KEY_ESCAPE,
+    Enter => KEY_ENTER,
+    Tab => KEY_TAB,
+    Backspace => KEY_BACKSPACE,
+    Insert => KEY_INSERT,
+    Delete => KEY_DELETE,
+    Right => KEY_RIGHT,
+    Left => KEY_LEFT,
+    Down => KEY_DOWN,
+    Up => KEY_UP,
+    PageUp => KEY_PAGE_UP,
+    PageDown => KEY_PAGE_DOWN,
+    Home => KEY_HOME,
+    End => KEY_END,
+    CapsLock => KEY_CAPS_LOCK,
+    ScrollLock => KEY_SCROLL_LOCK,
+    NumLock => KEY_NUM_LOCK,
+    PrintScreen => KEY_PRINT_SCREEN,
+    Pause => KEY_PAUSE,
+    F1 => KEY_F1,
+    F2 => KEY_F2,
+    F3 => KEY_F3,
+    F4 => KEY_F4,
+    F5 => KEY_F5,
+    F6 => KEY_F6,
+    F7 => KEY_F7,
+    F8 => KEY_F8,
+    F9 => KEY_F9,
+    F10 => KEY_F10,
+    F11 => KEY_F11,
+    F12 => KEY_F12,
+    F13 => KEY_F13,
+    F14 => KEY_F14,
+    F15 => KEY_F15,
+    F16 => KEY_F16,
+    F17 => KEY_F17,
+    F18 => KEY_F18,
+    F19 => KEY_F19,
+    F20 => KEY_F20,
+    F21 => KEY_F21,
+    F22 => KEY_F22,
+    F23 => KEY_F23,
+    F24 => KEY_F24,
+    F25 => KEY_F25,
+    Kp0 => KEY_KP_0,
+    Kp1 => KEY_KP_1,
+    Kp2 => KEY_KP_2,
+    Kp3 => KEY_KP_3,
+    Kp4 => KEY_KP_4,
+    Kp5 => KEY_KP_5,
+    Kp6 => KEY_KP_6,
+    Kp7 => KEY_KP_7,
+    Kp8 => KEY_KP_8,
+    Kp9 => KEY_KP_9,
+    KpDecimal => KEY_KP_DECIMAL,
+    KpDivide => KEY_KP_DIVIDE,
+    KpMultiply => KEY_KP_MULTIPLY,
+    KpSubtract => KEY_KP_SUBTRACT,
+    KpAdd => KEY_KP_ADD,
+    KpEnter => KEY_KP_ENTER,
+    KpEqual => KEY_KP_EQUAL,
+    LeftShift => KEY_LEFT_SHIFT,
+    LeftControl => KEY_LEFT_CONTROL,
+    LeftAlt => KEY_LEFT_ALT,
+    LeftSuper => KEY_LEFT_SUPER,
+    RightShift => KEY_RIGHT_SHIFT,
+    RightControl => KEY_RIGHT_CONTROL,
+    RightAlt => KEY_RIGHT_ALT,
+    RightSuper => KEY_RIGHT_SUPER,
+    Menu => KEY_MENU
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Button4,
+    Button5,
+    Button6,
+    Button7,
+    Button8,
+}
+
+impl MouseButton {
+    pub fn to_glfw(&self) -> c_int {
+        use MouseButton::*;
+        match self {
+            Left => MOUSE_BUTTON_LEFT,
+            Right => MOUSE_BUTTON_RIGHT,
+            Middle => MOUSE_BUTTON_MIDDLE,
+            Button4 => MOUSE_BUTTON_4,
+            Button5 => MOUSE_BUTTON_5,
+            Button6 => MOUSE_BUTTON_6,
+            Button7 => MOUSE_BUTTON_7,
+            Button8 => MOUSE_BUTTON_8,
+        }
+    }
+
+    pub fn from_glfw(code: c_int) -> Self {
+        use MouseButton::*;
+        match code {
+            MOUSE_BUTTON_RIGHT => Right,
+            MOUSE_BUTTON_MIDDLE => Middle,
+            MOUSE_BUTTON_4 => Button4,
+            MOUSE_BUTTON_5 => Button5,
+            MOUSE_BUTTON_6 => Button6,
+            MOUSE_BUTTON_7 => Button7,
+            MOUSE_BUTTON_8 => Button8,
+            _ => Left,
+        }
+    }
+
+    pub fn all() -> &'static [MouseButton] {
+        use MouseButton::*;
+        &[Left, Right, Middle, Button4, Button5, Button6, Button7, Button8]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Press,
+    Release,
+    Repeat,
+}
+
+impl Action {
+    fn from_glfw(action: c_int) -> Self {
+        match action {
+            PRESS => Action::Press,
+            REPEAT => Action::Repeat,
+            _ => Action::Release,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub super_key: bool,
+    pub caps_lock: bool,
+    pub num_lock: bool,
+}
+
+impl Modifiers {
+    pub fn from_glfw(mods: c_int) -> Self {
+        Self {
+            shift: mods & MOD_SHIFT != 0,
+            control: mods & MOD_CONTROL != 0,
+            alt: mods & MOD_ALT != 0,
+            super_key: mods & MOD_SUPER != 0,
+            caps_lock: mods & MOD_CAPS_LOCK != 0,
+            num_lock: mods & MOD_NUM_LOCK != 0,
+        }
+    }
+}
+
+// sizes are in screen coordinates, the cursor is relative to the top left
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Key { key: Key, scancode: i32, action: Action, modifiers: Modifiers },
+    MouseButton { button: MouseButton, action: Action, modifiers: Modifiers },
+    CursorMoved(f32, f32),
+    CursorEntered,
+    CursorLeft,
+    Scroll(f32, f32),
+    Char(char),
+    Resized(usize, usize),
+    // in pixels
+    FramebufferResized(usize, usize),
+    ContentScaleChanged(f32, f32),
+    Focused,
+    Unfocused,
+    CloseRequested,
+    // with the joystick id, see Gamepads
+    JoystickConnected(i32),
+    JoystickDisconnected(i32),
+}
+
+// the state of the keyboard and mouse as built up from the events, with the
+// edges of the last frame
+#[derive(Default)]
+pub struct Input {
+    events: Vec<Event>,
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>,
+    keys_released: HashSet<Key>,
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+    modifiers: Modifiers,
+    scroll: (f32, f32),
+    text: String,
+    cursor_inside: bool,
+    focused: bool,
+}
+
+impl Input {
+
+    pub fn ne() -> Self {
+        Self { cursor_inside: true, focused: true, ..Default::default() }
+    }
+
+    // forgets the edges of the last frame and applies the new events
+    pub fn begin_frame(&mut self, events: Vec<Event>) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.scroll = (0.0, 0.0);
+        self.text.clear();
+
+        for event in &events {
+            match *event {
+                Event::Key { key, action, modifiers, .. } => {
+                    self.modifiers = modifiers;
+                    // all keys without a name are Unknown, their state can not be told apart
+                    if key == Key::Unknown { continue }
+                    match action {
+                        Action::Press => {
+                            self.keys_down.insert(key);
+                            self.keys_pressed.insert(key);
+                        }
+                        Action::Release => {
+                            self.keys_down.remove(&key);
+                            self.keys_released.insert(key);
+                        }
+                        Action::Repeat => {}
+                    }
+                }
+                Event::MouseButton { button, action, modifiers } => {
+                    self.modifiers = modifiers;
+                    match action {
+                        Action::Press => {
+                            self.buttons_down.insert(button);
+                            self.buttons_pressed.insert(button);
+                        }
+                        Action::Release => {
+                            self.buttons_down.remove(&button);
+                            self.buttons_released.insert(button);
+                        }
+                        Action::Repeat => {}
+                    }
+                }
+                Event::Scroll(x, y) => {
+                    self.scroll.0 += x;
+                    self.scroll.1 += y;
+                }
+                Event::Char(c) => self.text.push(c),
+                Event::CursorEntered => self.cursor_inside = true,
+                Event::CursorLeft => self.cursor_inside = false,
+                Event::Focused => self.focused = true,
+                // keys released while unfocused are never reported
+                Event::Unfocused => {
+                    self.focused = false;
+                    self.keys_released.extend(self.keys_down.drain());
+                    self.buttons_released.extend(self.buttons_down.drain());
+                }
+                _ => {}
+            }
+        }
+        self.events = events;
+    }
+
+    pub fn events(&self) -> &[Event] { &self.events }
+
+    pub fn is_key_down(&self, key: Key) -> bool { self.keys_down.contains(&key) }
+    pub fn is_key_pressed_this_frame(&self, key: Key) -> bool { self.keys_pressed.contains(&key) }
+    pub fn is_key_released_this_frame(&self, key: Key) -> bool { self.keys_released.contains(&key) }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool { self.buttons_down.contains(&button) }
+    pub fn is_button_pressed_this_frame(&self, button: MouseButton) -> bool { self.buttons_pressed.contains(&button) }
+    pub fn is_button_released_this_frame(&self, button: MouseButton) -> bool { self.buttons_released.contains(&button) }
+
+    pub fn get_modifiers(&self) -> Modifiers { self.modifiers }
+    // summed over the frame
+    pub fn get_scroll(&self) -> (f32, f32) { self.scroll }
+    // typed this frame
+    pub fn get_text(&self) -> &str { &self.text }
+    pub fn is_cursor_inside(&self) -> bool { self.cursor_inside }
+    pub fn is_focused(&self) -> bool { self.focused }
+}
+
+// CALLBACKS
+// the window user pointer is the RefCell<Vec<Event>> of the Window
+
+pub(crate) unsafe fn set_callbacks(window: *mut GLFWwindow) {
+    glfwSetKeyCallback(window, Some(on_key));
+    glfwSetMouseButtonCallback(window, Some(on_mouse_button));
+    glfwSetCursorPosCallback(window, Some(on_cursor_pos));
+    glfwSetCursorEnterCallback(window, Some(on_cursor_enter));
+    glfwSetScrollCallback(window, Some(on_scroll));
+    glfwSetCharCallback(window, Some(on_char));
+    glfwSetWindowSizeCallback(window, Some(on_resize));
+    glfwSetFramebufferSizeCallback(window, Some(on_framebuffer_resize));
+    glfwSetWindowContentScaleCallback(window, Some(on_content_scale));
+    glfwSetWindowFocusCallback(window, Some(on_focus));
+    glfwSetWindowCloseCallback(window, Some(on_close));
+}
+
+fn push(window: *mut GLFWwindow, event: Event) {
+    unsafe {
+        let queue = glfwGetWindowUserPointer(window) as *const std::cell::RefCell<Vec<Event>>;
+        if let Some(queue) = queue.as_ref() {
+            queue.borrow_mut().push(event);
+        }
+    }
+}
+
+extern "C" fn on_key(window: *mut GLFWwindow, key: c_int, scancode: c_int, action: c_int, mods: c_int) {
+    push(window, Event::Key {
+        key: Key::from_glfw(key),
+        scancode,
+        action: Action::from_glfw(action),
+        modifiers: Modifiers::from_glfw(mods),
+    });
+}
+
+extern "C" fn on_mouse_button(window: *mut GLFWwindow, button: c_int, action: c_int, mods: c_int) {
+    push(window, Event::MouseButton {
+        button: MouseButton::from_glfw(button),
+        action: Action::from_glfw(action),
+        modifiers: Modifiers::from_glfw(mods),
+    });
+}
+
+extern "C" fn on_cursor_pos(window: *mut GLFWwindow, x: c_double, y: c_double) {
+    push(window, Event::CursorMoved(x as f32, y as f32));
+}
+
+extern "C" fn on_cursor_enter(window: *mut GLFWwindow, entered: c_int) {
+    push(window, if entered == TRUE { Event::CursorEntered } else { Event::CursorLeft });
+}
+
+extern "C" fn on_scroll(window: *mut GLFWwindow, x: c_double, y: c_double) {
+    push(window, Event::Scroll(x as f32, y as f32));
+}
+
+extern "C" fn on_char(window: *mut GLFWwindow, codepoint: c_uint) {
+    if let Some(c) = std::char::from_u32(codepoint) {
+        push(window, Event::Char(c));
+    }
+}
+
+extern "C" fn on_resize(window: *mut GLFWwindow, width: c_int, height: c_int) {
+    push(window, Event::Resized(width.max(0) as usize, height.max(0) as usize));
+}
+
+extern "C" fn on_framebuffer_resize(window: *mut GLFWwindow, width: c_int, height: c_int) {
+    push(window, Event::FramebufferResized(width.max(0) as usize, height.max(0) as usize));
+}
+
+extern "C" fn on_content_scale(window: *mut GLFWwindow, x: c_float, y: c_float) {
+    push(window, Event::ContentScaleChanged(x, y));
+}
+
+extern "C" fn on_focus(window: *mut GLFWwindow, focused: c_int) {
+    push(window, if focused == TRUE { Event::Focused } else { Event::Unfocused });
+}
+
+extern "C" fn on_close(window: *mut GLFWwindow) {
+    push(window, Event::CloseRequested);
+}
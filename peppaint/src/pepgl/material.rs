@@ -0,0 +1,184 @@
+use crate::{
+    Texture,
+    ShaderProgram,
+};
+
+use nalgebra_glm as glm;
+use std::path::Path;
+use std::rc::Rc;
+
+// texture units the material maps are bound to
+const DIFFUSE_MAP_UNIT: u32 = 0;
+const SPECULAR_MAP_UNIT: u32 = 1;
+const NORMAL_MAP_UNIT: u32 = 2;
+const METALLIC_ROUGHNESS_MAP_UNIT: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shading {
+    // just the diffuse color and map, no lighting
+    Unlit,
+    // ambient, diffuse, specular and shininess
+    BlinnPhong,
+    // diffuse is the base color, with metallic and roughness
+    Pbr,
+}
+
+#[derive(Clone)]
+pub struct Material {
+    pub name: String,
+    pub shading: Shading,
+    pub ambient: glm::Vec3,
+    pub diffuse: glm::Vec3,
+    pub specular: glm::Vec3,
+    pub shininess: f32,
+    pub dissolve: f32,
+    pub emissive: glm::Vec3,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub diffuse_map: Option<Rc<Texture>>,
+    pub specular_map: Option<Rc<Texture>>,
+    pub normal_map: Option<Rc<Texture>>,
+    // metallic in the blue, roughness in the green channel
+    pub metallic_roughness_map: Option<Rc<Texture>>,
+}
+
+impl Material {
+
+    pub fn none() -> Self {
+        Self {
+            name: String::new(),
+            shading: Shading::BlinnPhong,
+            ambient: glm::vec3(0.0, 0.0, 0.0),
+            diffuse: glm::vec3(1.0, 1.0, 1.0),
+            specular: glm::vec3(0.0, 0.0, 0.0),
+            shininess: 1.0,
+            dissolve: 1.0,
+            emissive: glm::vec3(0.0, 0.0, 0.0),
+            metallic: 0.0,
+            roughness: 1.0,
+            diffuse_map: None,
+            specular_map: None,
+            normal_map: None,
+            metallic_roughness_map: None,
+        }
+    }
+
+    pub fn unlit(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self {
+            shading: Shading::Unlit,
+            diffuse: glm::vec3(r, g, b),
+            dissolve: a,
+            ..Self::none()
+        }
+    }
+
+    pub fn blinn_phong(diffuse: glm::Vec3, specular: glm::Vec3, shininess: f32) -> Self {
+        Self {
+            shading: Shading::BlinnPhong,
+            ambient: diffuse,
+            diffuse,
+            specular,
+            shininess,
+            ..Self::none()
+        }
+    }
+
+    pub fn pbr(base_color: glm::Vec3, metallic: f32, roughness: f32) -> Self {
+        Self {
+            shading: Shading::Pbr,
+            diffuse: base_color,
+            metallic,
+            roughness,
+            ..Self::none()
+        }
+    }
+
+    pub fn textured(texture: Rc<Texture>) -> Self {
+        Self {
+            shading: Shading::Unlit,
+            diffuse_map: Some(texture),
+            ..Self::none()
+        }
+    }
+
+    pub(crate) fn from_obj(m: &tobj::Material, dir: &Path) -> Self {
+        let load = |tex: &str| -> Option<Rc<Texture>> {
+            if tex.is_empty() { return None }
+            let path = dir.join(tex.replace('\\', "/"));
+            if !path.exists() {
+                println!("Could not find texture {:?} of material {}", path, m.name);
+                return None
+            }
+            Some(Rc::new(Texture::from_path(path.to_str()?)))
+        };
+        Self {
+            name: m.name.clone(),
+            ambient: glm::make_vec3(&m.ambient),
+            diffuse: glm::make_vec3(&m.diffuse),
+            specular: glm::make_vec3(&m.specular),
+            shininess: m.shininess,
+            dissolve: m.dissolve,
+            diffuse_map: load(&m.diffuse_texture),
+            specular_map: load(&m.specular_texture),
+            normal_map: load(&m.normal_texture),
+            ..Self::none()
+        }
+    }
+
+    // sets the uMaterial uniforms that are used by the shading model, and that the
+    // program has, so shaders without materials stay quiet
+    pub fn bind(&self, program: &mut ShaderProgram) {
+        use Shading::*;
+
+        set_vec3(program, "uMaterial.diffuse", &self.diffuse);
+        set_float(program, "uMaterial.dissolve", self.dissolve);
+        let mut maps = vec![
+            (&self.diffuse_map, "uMaterial.diffuseMap", "uMaterial.hasDiffuseMap", DIFFUSE_MAP_UNIT),
+        ];
+
+        if self.shading != Unlit {
+            set_vec3(program, "uMaterial.emissive", &self.emissive);
+            maps.push((&self.normal_map, "uMaterial.normalMap", "uMaterial.hasNormalMap", NORMAL_MAP_UNIT));
+        }
+
+        match self.shading {
+            Unlit => {},
+            BlinnPhong => {
+                set_vec3(program, "uMaterial.ambient", &self.ambient);
+                set_vec3(program, "uMaterial.specular", &self.specular);
+                set_float(program, "uMaterial.shininess", self.shininess);
+                maps.push((&self.specular_map, "uMaterial.specularMap", "uMaterial.hasSpecularMap", SPECULAR_MAP_UNIT));
+            },
+            Pbr => {
+                set_float(program, "uMaterial.metallic", self.metallic);
+                set_float(program, "uMaterial.roughness", self.roughness);
+                maps.push((
+                    &self.metallic_roughness_map, 
+                    "uMaterial.metallicRoughnessMap", 
+                    "uMaterial.hasMetallicRoughnessMap", 
+                    METALLIC_ROUGHNESS_MAP_UNIT
+                ));
+            }
+        }
+
+        for (map, sampler, flag, unit) in maps.iter() {
+            set_int(program, flag, map.is_some() as i32);
+            if let Some(tex) = map {
+                tex.bind_to_unit(*unit);
+                set_int(program, sampler, *unit as i32);
+            }
+        }
+    }
+}
+
+fn set_vec3(program: &mut ShaderProgram, name: &str, v: &glm::Vec3) {
+    if program.has_uniform(name) { program.set_vec3f(name, v) }
+}
+
+fn set_float(program: &mut ShaderProgram, name: &str, f: f32) {
+    if program.has_uniform(name) { program.set_float(name, f) }
+}
+
+fn set_int(program: &mut ShaderProgram, name: &str, i: i32) {
+    if program.has_uniform(name) { program.set_int(name, i) }
+}
@@ -1,7 +1,9 @@
 use crate::pepgl::shader::ShaderProgram;
 use crate::pepgl::vao::VertexArrayObject;
 use crate::pepgl::vertex::Vertex;
+use crate::pepgl::bounds::Bounds;
 
+use std::cell::Cell;
 use std::fmt::Debug;
 
 // TODO: make similar to painting
@@ -13,6 +15,8 @@ pub struct Mesh<T: Vertex + Copy + Debug> {
     vao: VertexArrayObject,
     vertices: Vec<T>,
     indices: Vec<u32>, 
+    // computed on buffer
+    bounds: Cell<Option<Bounds>>,
 }
 
 impl<T: Vertex + Copy + Debug> Mesh<T> {
@@ -27,6 +31,7 @@ impl<T: Vertex + Copy + Debug> Mesh<T> {
             program: None, 
             vertices, 
             indices, 
+            bounds: Cell::new(None),
         };
         this.buffer();
         this
@@ -41,6 +46,7 @@ impl<T: Vertex + Copy + Debug> Mesh<T> {
             program: None, 
             vertices, 
             indices, 
+            bounds: Cell::new(None),
         }
     }
 
@@ -53,6 +59,7 @@ impl<T: Vertex + Copy + Debug> Mesh<T> {
             program: None, 
             vertices, 
             indices, 
+            bounds: Cell::new(None),
         }
     }
 
@@ -65,6 +72,7 @@ impl<T: Vertex + Copy + Debug> Mesh<T> {
             program: None,  
             vertices, 
             indices, 
+            bounds: Cell::new(None),
         }
     }
 
@@ -82,6 +90,12 @@ impl<T: Vertex + Copy + Debug> Mesh<T> {
     pub fn buffer(&self) {
         self.vao.bind();
         self.vao.buffer(&self.vertices, &self.indices);
+        self.bounds.set(Bounds::from_vertices(&self.vertices));
+    }
+
+    // dynamic meshes that only subbuffer have to do this themselves
+    pub fn compute_bounds(&self) {
+        self.bounds.set(Bounds::from_vertices(&self.vertices));
     }
 
     pub fn subbuffer(&self) {
@@ -160,6 +174,10 @@ impl<T: Vertex + Copy + Debug> Mesh<T> {
         for v in v.iter() { self.vertices.push(*v) }
     }
 
+    pub fn get_vertices(&self) -> &[T] { &self.vertices }
+    pub fn get_indices(&self) -> &[u32] { &self.indices }
+    pub fn get_bounds(&self) -> Option<Bounds> { self.bounds.get() }
+
     // DEBUG 
 
     pub fn print_vertices(&self) {
@@ -7,24 +7,49 @@ pub use vao::*;
 pub mod window;
 pub use window::*;
 
+pub mod window_config;
+pub use window_config::*;
+
+pub mod input;
+pub use input::*;
+
+pub mod gamepad;
+pub use gamepad::*;
+
 pub mod vertex;
 pub use vertex::*;
 
+pub mod bounds;
+pub use bounds::*;
+
 pub mod mesh;
 pub use mesh::*;
 
 pub mod model;
 pub use model::*;
 
+pub mod material;
+pub use material::*;
+
+pub mod model_gltf;
+pub use model_gltf::*;
+
+pub mod export;
+pub use export::*;
+
 pub mod buffer;
 pub use buffer::*;
 
+pub mod framebuffer;
+pub use framebuffer::*;
+
 pub mod texture;
 pub use texture::Texture;
 
 use gl;
 
 pub fn gl_draw_tris(n_indices: usize) {
+    crate::count_draw(n_indices);
     unsafe { 
         gl::DrawElements(
             gl::TRIANGLES, 
@@ -1,13 +1,28 @@
 use crate::{
     VertexArrayObject,
-    Texture,
+    Material,
+    ShaderProgram,
+    Bounds,
+    Frustum,
+    CullStats,
+    Vec2,
+    Vec3,
+    std::PosNormTex,
 };
 
+use nalgebra_glm as glm;
 use std::path::Path;
 
 pub struct Model {
-    meshes: Vec<ModelMesh>,
-    materials: Vec<Material>
+    pub(crate) meshes: Vec<ModelMesh>,
+    pub(crate) materials: Vec<Material>,
+    // empty for flat formats like .obj
+    pub(crate) nodes: Vec<ModelNode>,
+    pub(crate) roots: Vec<usize>,
+    pub(crate) skins: Vec<crate::Skin>,
+    pub(crate) animations: Vec<crate::Animation>,
+    // in model space, with the node transforms applied
+    pub(crate) bounds: Option<Bounds>,
 }
 
 impl Model {
@@ -22,38 +37,181 @@ impl Model {
         if !obj.is_ok() {
             return Err("There is something wrong with the .obj file".to_string())
         }
-        let (objmodels, _objmaterials) = obj.unwrap();
+        let (objmodels, objmaterials) = obj.unwrap();
+
+        // texture paths in the .mtl are relative to the .obj
+        let dir = path.parent().unwrap_or(Path::new(""));
 
         let mut meshes = Vec::new();
-        let materials = Vec::new();
+        let materials = objmaterials
+            .iter()
+            .map(|m| Material::from_obj(m, dir))
+            .collect();
 
         for model in objmodels {
-            let mut vao = VertexArrayObject::new_static();
-            let n_indices = model.mesh.indices.len();
+            let mesh = &model.mesh;
+            let n_vertices = mesh.positions.len() / 3;
+            let has_normals = mesh.normals.len() == n_vertices * 3;
+            let has_texcoords = mesh.texcoords.len() == n_vertices * 2;
+
+            let mut vertices = Vec::with_capacity(n_vertices);
+            for i in 0..n_vertices {
+                let pos = Vec3(
+                    mesh.positions[3 * i],
+                    mesh.positions[3 * i + 1],
+                    mesh.positions[3 * i + 2]
+                );
+                let normal = if has_normals {
+                    Vec3(mesh.normals[3 * i], mesh.normals[3 * i + 1], mesh.normals[3 * i + 2])
+                } else { Vec3(0.0, 0.0, 0.0) };
+                let texcoords = if has_texcoords {
+                    Vec2(mesh.texcoords[2 * i], mesh.texcoords[2 * i + 1])
+                } else { Vec2(0.0, 0.0) };
+                vertices.push(PosNormTex { pos, normal, texcoords });
+            }
+
+            let vao = VertexArrayObject::new_static();
             vao.bind();
-            vao.buffer_indices(&model.mesh.indices);
-            vao.buffer_to_new_vbo(&model.mesh.positions);
-            vao.set_attrib_layout(0, 3, 12, 0, crate::Type::Float);
-            meshes.push(ModelMesh { vao, n_indices })
+            vao.buffer(&vertices, &mesh.indices);
+            meshes.push(ModelMesh {
+                vao,
+                n_indices: mesh.indices.len(),
+                material: mesh.material_id,
+                bounds: Bounds::from_vertices(&vertices),
+            })
         }
 
-        Ok(Self { meshes, materials })
+        Ok(Self {
+            meshes,
+            materials,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            skins: Vec::new(),
+            animations: Vec::new(),
+            bounds: None,
+        }.with_bounds())
+    }
+
+    // binds the program and sets the uMaterial uniforms of every mesh before drawing it,
+    // models with a node hierarchy also get the world matrix of each node as uModel
+    pub fn show(&self, program: &mut ShaderProgram) {
+        self.show_with(program, None);
+    }
+
+    // skips the meshes outside of the frustum, which should be in the same space as the model
+    pub fn show_culled(&self, program: &mut ShaderProgram, frustum: &Frustum, stats: &mut CullStats) {
+        self.show_with(program, Some((frustum, stats)));
     }
 
-    pub fn show(&self) {
-        for mesh in self.meshes.iter() {
-            mesh.vao.bind();
-            crate::gl_draw_tris(mesh.n_indices);
+    fn show_with(&self, program: &mut ShaderProgram, mut culling: Option<(&Frustum, &mut CullStats)>) {
+        program.bind();
+        if self.nodes.is_empty() {
+            for mesh in self.meshes.iter() {
+                if let Some((frustum, stats)) = culling.as_mut() {
+                    if !stats.test(frustum, mesh.bounds.as_ref(), &glm::identity()) { continue }
+                }
+                self.show_mesh(mesh, program);
+            }
+            return
+        }
+        for root in self.roots.iter() {
+            self.show_node(*root, &glm::identity(), program, &mut culling);
         }
     }
 
-}
+    fn show_node(
+        &self, 
+        index: usize, 
+        parent: &glm::Mat4, 
+        program: &mut ShaderProgram, 
+        culling: &mut Option<(&Frustum, &mut CullStats)>
+    ) {
+        let node = &self.nodes[index];
+        let world = parent * node.transform;
+        if !node.meshes.is_empty() {
+            program.set_mat4f("uModel", world.as_ptr());
+            for mesh in node.meshes.iter() {
+                let mesh = &self.meshes[*mesh];
+                if let Some((frustum, stats)) = culling.as_mut() {
+                    if !stats.test(frustum, mesh.bounds.as_ref(), &world) { continue }
+                }
+                self.show_mesh(mesh, program);
+            }
+        }
+        for child in node.children.iter() {
+            self.show_node(*child, &world, program, culling);
+        }
+    }
+
+    fn show_mesh(&self, mesh: &ModelMesh, program: &mut ShaderProgram) {
+        match mesh.material {
+            Some(i) => self.materials[i].bind(program),
+            None => Material::none().bind(program)
+        };
+        mesh.vao.bind();
+        crate::gl_draw_tris(mesh.n_indices);
+    }
+
+    // world matrices of all nodes, in the same order as get_nodes
+    pub fn world_transforms(&self) -> Vec<glm::Mat4> {
+        let mut worlds = vec![glm::identity(); self.nodes.len()];
+        let mut stack: Vec<(usize, glm::Mat4)> = self.roots
+            .iter()
+            .map(|r| (*r, glm::identity()))
+            .collect();
+        while let Some((index, parent)) = stack.pop() {
+            let world = parent * self.nodes[index].transform;
+            for child in self.nodes[index].children.iter() {
+                stack.push((*child, world));
+            }
+            worlds[index] = world;
+        }
+        worlds
+    }
+
+    pub(crate) fn with_bounds(mut self) -> Self {
+        let mut bounds: Option<Bounds> = None;
+        let mut add = |b: &Option<Bounds>, m: &glm::Mat4| {
+            if let Some(b) = b {
+                let b = b.transformed(m);
+                bounds = Some(match bounds { Some(all) => all.merge(&b), None => b });
+            }
+        };
+        if self.nodes.is_empty() {
+            for mesh in self.meshes.iter() { add(&mesh.bounds, &glm::identity()) }
+        } else {
+            let worlds = self.world_transforms();
+            for (node, world) in self.nodes.iter().zip(worlds.iter()) {
+                for mesh in node.meshes.iter() { add(&self.meshes[*mesh].bounds, world) }
+            }
+        }
+        self.bounds = bounds;
+        self
+    }
+
+    pub fn get_bounds(&self) -> Option<Bounds> { self.bounds }
+    pub fn get_materials(&self) -> &[Material] { &self.materials }
+    pub fn get_materials_mut(&mut self) -> &mut [Material] { &mut self.materials }
+    pub fn get_nodes(&self) -> &[ModelNode] { &self.nodes }
+    pub fn get_roots(&self) -> &[usize] { &self.roots }
+    pub fn get_skins(&self) -> &[crate::Skin] { &self.skins }
+    pub fn get_animations(&self) -> &[crate::Animation] { &self.animations }
 
-struct ModelMesh {
-    vao: VertexArrayObject,
-    n_indices: usize
 }
 
-struct Material {
+pub(crate) struct ModelMesh {
+    pub(crate) vao: VertexArrayObject,
+    pub(crate) n_indices: usize,
+    pub(crate) material: Option<usize>,
+    pub(crate) bounds: Option<Bounds>,
+}
 
-}
\ No newline at end of file
+pub struct ModelNode {
+    pub name: Option<String>,
+    // local transform, relative to the parent
+    pub transform: glm::Mat4,
+    pub children: Vec<usize>,
+    // indices into the meshes of the Model, one per primitive
+    pub meshes: Vec<usize>,
+    pub skin: Option<usize>,
+}
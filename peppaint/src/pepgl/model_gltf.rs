@@ -0,0 +1,307 @@
+use crate::{
+    Model,
+    ModelNode,
+    Material,
+    Shading,
+    VertexArrayObject,
+    Texture,
+    Vec2,
+    Vec3,
+    std::PosNormTex,
+    Type,
+    Bounds,
+};
+use crate::model::ModelMesh;
+use crate::texture::{TextureParams, TexWrapping, TexFiltering};
+
+use gltf::image::Format;
+use image::RgbaImage;
+use nalgebra_glm as glm;
+use std::path::Path;
+use std::rc::Rc;
+
+// vertex attribute locations for skinned meshes, after the PosNormTex ones (0 to 2)
+// and the tangent location 3 that PosNormTexTan uses
+pub const JOINTS_LOCATION: usize = 4;
+pub const WEIGHTS_LOCATION: usize = 5;
+
+pub struct Skin {
+    pub name: Option<String>,
+    // node indices of the joints, in the order the vertex joint indices refer to
+    pub joints: Vec<usize>,
+    pub inverse_bind_matrices: Vec<glm::Mat4>,
+    pub skeleton: Option<usize>,
+}
+
+pub struct Animation {
+    pub name: Option<String>,
+    pub channels: Vec<AnimationChannel>,
+}
+
+impl Animation {
+    pub fn duration(&self) -> f32 {
+        self.channels
+            .iter()
+            .filter_map(|c| c.times.last())
+            .fold(0.0, |a, b| a.max(*b))
+    }
+}
+
+pub struct AnimationChannel {
+    pub node: usize,
+    pub interpolation: Interpolation,
+    // keyframe times in seconds
+    pub times: Vec<f32>,
+    // for CubicSpline there are three values per keyframe: in-tangent, value, out-tangent
+    pub values: AnimationValues,
+}
+
+pub enum AnimationValues {
+    Translations(Vec<glm::Vec3>),
+    Rotations(Vec<glm::Quat>),
+    Scales(Vec<glm::Vec3>),
+    // one weight per morph target per keyframe
+    Weights(Vec<f32>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    Linear,
+    Step,
+    CubicSpline,
+}
+
+impl Model {
+
+    // loads both .gltf (with external or embedded buffers) and .glb files
+    pub fn dot_gltf(path: &Path) -> Result<Self, String> {
+
+        if !path.exists() {
+            return Err("Incorrect path to .gltf file".to_string())
+        }
+
+        let (document, buffers, images) = match gltf::import(path) {
+            Ok(import) => import,
+            Err(e) => return Err(format!("There is something wrong with the .gltf file: {}", e))
+        };
+        let get_buffer = |b: gltf::Buffer| Some(&*buffers[b.index()]);
+
+        // one Texture per image, shared between all textures that use it
+        let mut image_textures: Vec<Option<Rc<Texture>>> = vec![None; images.len()];
+        let mut textures = Vec::new();
+        for texture in document.textures() {
+            let index = texture.source().index();
+            if image_textures[index].is_none() {
+                let img = match to_rgba_image(&images[index]) {
+                    Some(img) => img,
+                    None => return Err("Could not convert .gltf image to rgba".to_string())
+                };
+                let sampler = texture.sampler();
+                let wrapping = match sampler.wrap_s() {
+                    gltf::texture::WrappingMode::ClampToEdge => TexWrapping::ClampEdge,
+                    gltf::texture::WrappingMode::MirroredRepeat => TexWrapping::MirrorRepeat,
+                    gltf::texture::WrappingMode::Repeat => TexWrapping::Repeat,
+                };
+                let filtering = match sampler.mag_filter() {
+                    Some(gltf::texture::MagFilter::Nearest) => TexFiltering::Nearest,
+                    _ => TexFiltering::Linear,
+                };
+                let params = TextureParams::new(wrapping, filtering);
+                image_textures[index] = Some(Rc::new(Texture::rbga_with_params(img, params)));
+            }
+            textures.push(image_textures[index].clone().unwrap());
+        }
+        let get_texture = |info: Option<gltf::texture::Info>| {
+            info.map(|i| textures[i.texture().index()].clone())
+        };
+
+        let materials = document.materials().map(|m| {
+            let pbr = m.pbr_metallic_roughness();
+            let base = pbr.base_color_factor();
+            Material {
+                name: m.name().unwrap_or("").to_string(),
+                shading: Shading::Pbr,
+                diffuse: glm::vec3(base[0], base[1], base[2]),
+                dissolve: base[3],
+                emissive: glm::make_vec3(&m.emissive_factor()),
+                metallic: pbr.metallic_factor(),
+                roughness: pbr.roughness_factor(),
+                diffuse_map: get_texture(pbr.base_color_texture()),
+                metallic_roughness_map: get_texture(pbr.metallic_roughness_texture()),
+                normal_map: m.normal_texture().map(|n| textures[n.texture().index()].clone()),
+                ..Material::none()
+            }
+        }).collect();
+
+        // every primitive becomes a ModelMesh, mesh_primitives maps gltf meshes to them
+        let mut meshes = Vec::new();
+        let mut mesh_primitives = Vec::new();
+        for mesh in document.meshes() {
+            let mut primitives = Vec::new();
+            for primitive in mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    println!("Skipping .gltf primitive that is not made of triangles");
+                    continue
+                }
+                let reader = primitive.reader(get_buffer);
+
+                let positions: Vec<[f32; 3]> = match reader.read_positions() {
+                    Some(p) => p.collect(),
+                    None => continue
+                };
+                let n_vertices = positions.len();
+                let normals: Vec<[f32; 3]> = match reader.read_normals() {
+                    Some(n) => n.collect(),
+                    None => vec![[0.0; 3]; n_vertices]
+                };
+                let texcoords: Vec<[f32; 2]> = match reader.read_tex_coords(0) {
+                    Some(t) => t.into_f32().collect(),
+                    None => vec![[0.0; 2]; n_vertices]
+                };
+                let indices: Vec<u32> = match reader.read_indices() {
+                    Some(i) => i.into_u32().collect(),
+                    None => (0..n_vertices as u32).collect()
+                };
+
+                let mut vertices = Vec::with_capacity(n_vertices);
+                for i in 0..n_vertices {
+                    let (p, n, t) = (positions[i], normals[i], texcoords[i]);
+                    vertices.push(PosNormTex {
+                        pos: Vec3(p[0], p[1], p[2]),
+                        normal: Vec3(n[0], n[1], n[2]),
+                        texcoords: Vec2(t[0], t[1])
+                    });
+                }
+
+                let mut vao = VertexArrayObject::new_static();
+                vao.bind();
+                vao.buffer(&vertices, &indices);
+
+                if let (Some(joints), Some(weights)) = (reader.read_joints(0), reader.read_weights(0)) {
+                    let joints: Vec<[u16; 4]> = joints.into_u16().collect();
+                    let weights: Vec<[f32; 4]> = weights.into_f32().collect();
+                    vao.buffer_to_new_vbo(&joints);
+                    vao.set_int_attrib_layout(JOINTS_LOCATION, 4, 8, 0, Type::Ushort);
+                    vao.buffer_to_new_vbo(&weights);
+                    vao.set_attrib_layout(WEIGHTS_LOCATION, 4, 16, 0, Type::Float, false);
+                }
+
+                primitives.push(meshes.len());
+                meshes.push(ModelMesh {
+                    vao,
+                    n_indices: indices.len(),
+                    material: primitive.material().index(),
+                    bounds: Bounds::from_vertices(&vertices),
+                });
+            }
+            mesh_primitives.push(primitives);
+        }
+
+        let nodes = document.nodes().map(|n| {
+            let m = n.transform().matrix();
+            let flat: Vec<f32> = m.iter().flat_map(|col| col.iter().cloned()).collect();
+            ModelNode {
+                name: n.name().map(|s| s.to_string()),
+                transform: glm::make_mat4(&flat),
+                children: n.children().map(|c| c.index()).collect(),
+                meshes: match n.mesh() {
+                    Some(mesh) => mesh_primitives[mesh.index()].clone(),
+                    None => Vec::new()
+                },
+                skin: n.skin().map(|s| s.index()),
+            }
+        }).collect();
+
+        let scene = document.default_scene().or_else(|| document.scenes().next());
+        let roots = match scene {
+            Some(scene) => scene.nodes().map(|n| n.index()).collect(),
+            None => Vec::new()
+        };
+
+        let skins = document.skins().map(|s| {
+            let reader = s.reader(get_buffer);
+            let inverse_bind_matrices = match reader.read_inverse_bind_matrices() {
+                Some(mats) => mats.map(|m| {
+                    let flat: Vec<f32> = m.iter().flat_map(|col| col.iter().cloned()).collect();
+                    glm::make_mat4(&flat)
+                }).collect(),
+                None => vec![glm::identity(); s.joints().count()]
+            };
+            Skin {
+                name: s.name().map(|s| s.to_string()),
+                joints: s.joints().map(|j| j.index()).collect(),
+                inverse_bind_matrices,
+                skeleton: s.skeleton().map(|n| n.index()),
+            }
+        }).collect();
+
+        let animations = document.animations().map(|a| {
+            let channels = a.channels().filter_map(|c| {
+                let reader = c.reader(get_buffer);
+                let times = reader.read_inputs()?.collect();
+                use gltf::animation::util::ReadOutputs::*;
+                let values = match reader.read_outputs()? {
+                    Translations(t) => AnimationValues::Translations(t.map(|v| glm::make_vec3(&v)).collect()),
+                    Rotations(r) => AnimationValues::Rotations(r.into_f32().map(|q| glm::quat(q[0], q[1], q[2], q[3])).collect()),
+                    Scales(s) => AnimationValues::Scales(s.map(|v| glm::make_vec3(&v)).collect()),
+                    MorphTargetWeights(w) => AnimationValues::Weights(w.into_f32().collect()),
+                };
+                let interpolation = match c.sampler().interpolation() {
+                    gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                    gltf::animation::Interpolation::Step => Interpolation::Step,
+                    gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+                };
+                Some(AnimationChannel {
+                    node: c.target().node().index(),
+                    interpolation,
+                    times,
+                    values
+                })
+            }).collect();
+            Animation {
+                name: a.name().map(|s| s.to_string()),
+                channels
+            }
+        }).collect();
+
+        Ok(Self {
+            meshes,
+            materials,
+            nodes,
+            roots,
+            skins,
+            animations,
+            bounds: None,
+        }.with_bounds())
+    }
+}
+
+fn to_rgba_image(data: &gltf::image::Data) -> Option<RgbaImage> {
+    let (channels, bytes) = match data.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 | Format::B8G8R8 => (3, 1),
+        Format::R8G8B8A8 | Format::B8G8R8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+    };
+    let bgr = data.format == Format::B8G8R8 || data.format == Format::B8G8R8A8;
+
+    let n_pixels = (data.width * data.height) as usize;
+    let mut rgba = Vec::with_capacity(n_pixels * 4);
+    for pixel in data.pixels.chunks(channels * bytes).take(n_pixels) {
+        // keep the most significant byte of 16 bit channels
+        let c = |i: usize| pixel[i * bytes + bytes - 1];
+        let (r, g, b, a) = match channels {
+            1 => (c(0), c(0), c(0), 255),
+            2 => (c(0), c(0), c(0), c(1)),
+            3 => (c(0), c(1), c(2), 255),
+            _ => (c(0), c(1), c(2), c(3)),
+        };
+        if bgr { rgba.extend_from_slice(&[b, g, r, a]) }
+        else { rgba.extend_from_slice(&[r, g, b, a]) }
+    }
+    RgbaImage::from_raw(data.width, data.height, rgba)
+}
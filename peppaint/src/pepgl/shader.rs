@@ -2,6 +2,7 @@ use gl::types::*;
 use std::ffi::{CString};
 use crate::types::{Mat4, Vec4};
 use std::collections::HashMap;
+use nalgebra_glm as glm;
 
 pub struct ShaderProgram {
     id: GLuint,
@@ -68,12 +69,24 @@ impl ShaderProgram {
     }
 
     pub fn get_location(&mut self, name: &str) -> GLint {
+        let cached = self.loc_cache.contains_key(name);
+        let loc = self.lookup(name);
+        // misses are cached too, so the warning is only printed once
+        if loc == -1 && !cached { println!("uniform {} does not exist or starts with reserved prefix", name); }
+        loc
+    }
+
+    // for uniforms a shader may leave out, without the warning
+    pub fn has_uniform(&mut self, name: &str) -> bool {
+        self.lookup(name) != -1
+    }
+
+    fn lookup(&mut self, name: &str) -> GLint {
         match self.loc_cache.get(name) {
             Some(loc) => *loc,
             None => {
                 let loc: GLint = unsafe { gl::GetUniformLocation(self.id, const_char_ptr!(name) as *const i8) };
-                if loc == -1 { println!("uniform name does not exist or starts with reserved prefix"); }
-                else { self.loc_cache.insert(name.to_string(), loc); }
+                self.loc_cache.insert(name.to_string(), loc);
                 loc
             }
         }
@@ -100,6 +113,20 @@ impl ShaderProgram {
         }
     }
 
+    pub fn set_vec3f(&mut self, name: &str, vec3f: &glm::Vec3) {
+        unsafe {
+            let loc = self.get_location(name);
+            gl::Uniform3f(loc, vec3f.x, vec3f.y, vec3f.z);
+        }
+    }
+
+    pub fn set_vec4f(&mut self, name: &str, vec4f: &glm::Vec4) {
+        unsafe {
+            let loc = self.get_location(name);
+            gl::Uniform4f(loc, vec4f.x, vec4f.y, vec4f.z, vec4f.w);
+        }
+    }
+
     pub fn set_float(&mut self, name: &str, float: GLfloat) {
         unsafe {
             let loc = self.get_location(name);
@@ -23,6 +23,10 @@ impl Texture {
         Self::rbga_with_params(img, DEFAULT_TEX_PARAMS)
     }
 
+    pub fn from_rgba(img: RgbaImage) -> Self {
+        Self::rbga_with_params(img, DEFAULT_TEX_PARAMS)
+    }
+
     // WHITE TEXTURE HERE
 
     pub fn rbga_with_params(img: RgbaImage, params: TextureParams) -> Self {
@@ -51,11 +55,78 @@ impl Texture {
         }
     }
 
+    // a depth texture to render into, reads outside of it are at the far plane
+    pub fn depth(width: usize, height: usize) -> Self {
+        unsafe {
+            let mut id = 0;
+            gl::GenTextures(1, &mut id);
+            gl::BindTexture(gl::TEXTURE_2D, id);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::DEPTH_COMPONENT24 as Int,
+                width as Int,
+                height as Int,
+                0,
+                gl::DEPTH_COMPONENT,
+                gl::FLOAT,
+                std::ptr::null()
+            );
+            let border = [1.0 as Float; 4];
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as Int);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as Int);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_BORDER as Int);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_BORDER as Int);
+            gl::TexParameterfv(gl::TEXTURE_2D, gl::TEXTURE_BORDER_COLOR, border.as_ptr());
+            Self { id }
+        }
+    }
+
+    // an RGBA texture to render into, for offscreen passes
+    pub fn color(width: usize, height: usize) -> Self {
+        unsafe {
+            let mut id = 0;
+            gl::GenTextures(1, &mut id);
+            gl::BindTexture(gl::TEXTURE_2D, id);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as Int,
+                width as Int,
+                height as Int,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null()
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as Int);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as Int);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as Int);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as Int);
+            Self { id }
+        }
+    }
+
+    pub(crate) fn get_id(&self) -> Uint { self.id }
+
     pub fn bind(&self) {
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, self.id);
         }
     }
+
+    pub fn bind_to_unit(&self, unit: Uint) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+        }
+    }
+}
+
+impl Drop for Texture {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteTextures(1, &self.id) }
+    }
 }
 
 pub struct TextureParams {
@@ -64,6 +135,10 @@ pub struct TextureParams {
 }
 
 impl TextureParams {
+    pub fn new(wrapping: TexWrapping, filtering: TexFiltering) -> Self {
+        Self { wrapping, filtering }
+    }
+
     fn set(&self) {
         use TexWrapping::*;
         use TexFiltering::*;
@@ -1,15 +1,42 @@
 use gl::types::*;
+use std::cell::{ Cell, RefCell };
 use std::path::Path;
-use crate::{Vertex, Buffer, BufferType, DrawType};
+use crate::{Vertex, Buffer, BufferType, DrawType, current_context};
 
+// vertex arrays are not shared between the contexts of different windows, so
+// one is made for every context the object is bound in and the attributes are
+// set again there from the ones kept here
 pub struct VertexArrayObject {
-    vao: u32,
+    vaos: RefCell<Vec<ContextVao>>,
+    attribs: RefCell<Vec<Attrib>>,
+    // goes up whenever an attribute is set
+    version: Cell<usize>,
     vbo: Buffer,
     vbos: Vec<Buffer>,
     ibo: Buffer, 
     drawtype: DrawType
 }
 
+struct ContextVao {
+    context: usize,
+    vao: u32,
+    // of the attributes it has, None before the first bind
+    version: Option<usize>,
+}
+
+#[derive(Clone, Copy)]
+struct Attrib {
+    // the vertex buffer the attribute reads from
+    buffer: u32,
+    location: usize,
+    n_elements: usize,
+    stride: usize,
+    byte_offset: usize,
+    type_: GLenum,
+    normalized: bool,
+    integer: bool,
+}
+
 impl VertexArrayObject { 
 
     pub fn new(drawtype: DrawType) -> Self {
@@ -22,9 +49,16 @@ impl VertexArrayObject {
             drawtype
         );
         let vbos = Vec::new();
-        let mut vao: u32 = 0;
-        unsafe { gl::GenVertexArrays(1, &mut vao); };
-        Self { vao, vbo, vbos, ibo, drawtype }
+        let vao = ContextVao { context: current_context(), vao: gen_vao(), version: Some(0) };
+        Self { 
+            vaos: RefCell::new(vec![vao]), 
+            attribs: RefCell::new(vec![]), 
+            version: Cell::new(0), 
+            vbo, 
+            vbos, 
+            ibo, 
+            drawtype 
+        }
     }
 
     pub fn new_static() -> Self {
@@ -35,8 +69,27 @@ impl VertexArrayObject {
         Self::new(DrawType::Dynamic)
     }
 
+    // binds the vertex array of the current context, which is made on the first
+    // bind in a context and brought up to date if attributes changed elsewhere
     pub fn bind(&self) {
-        unsafe { gl::BindVertexArray(self.vao) }
+        let context = current_context();
+        let mut vaos = self.vaos.borrow_mut();
+        let i = match vaos.iter().position(|v| v.context == context) {
+            Some(i) => i,
+            None => {
+                vaos.push(ContextVao { context, vao: gen_vao(), version: None });
+                vaos.len() - 1
+            }
+        };
+        unsafe { gl::BindVertexArray(vaos[i].vao) }
+        let version = self.version.get();
+        if vaos[i].version != Some(version) {
+            self.ibo.bind();
+            for attrib in self.attribs.borrow().iter() {
+                apply(attrib);
+            }
+            vaos[i].version = Some(version);
+        }
     }
 
     pub fn init_buffers<T: Vertex>(&self, verlen: usize, indlen: usize) {
@@ -93,7 +146,11 @@ impl VertexArrayObject {
     fn set_vertex_layout<T: Vertex>(&self) {
         let vl = T::get_layout();
         for al in vl.attrib_layouts {
-            self.set_attrib_layout(al.location, al.n_elements, vl.stride, al.byte_offset, al.type_);
+            if al.integer {
+                self.set_int_attrib_layout(al.location, al.n_elements, vl.stride, al.byte_offset, al.type_);
+            } else {
+                self.set_attrib_layout(al.location, al.n_elements, vl.stride, al.byte_offset, al.type_, al.normalized);
+            }
         }
     }
 
@@ -104,24 +161,107 @@ impl VertexArrayObject {
         stride: usize, 
         byte_offset: usize, 
         type_: crate::Type,
+        normalized: bool,
     ) {
-        unsafe {
-            gl::EnableVertexAttribArray(location as GLuint);
-            gl::VertexAttribPointer(
-                location as GLuint, 
-                n_elements as GLint, 
-                type_.to_gl_type_enum(), 
-                gl::FALSE, 
-                stride as GLsizei, 
-                byte_offset as *const GLvoid
-            );
+        if type_.is_packed() && n_elements != 4 {
+            panic!("Packed 2_10_10_10 attributes need 4 elements");
+        }
+        self.set_attrib(Attrib {
+            buffer: bound_vertex_buffer(),
+            location, 
+            n_elements, 
+            stride, 
+            byte_offset, 
+            type_: type_.to_gl_type_enum(), 
+            normalized, 
+            integer: false,
+        });
+    }
+
+    pub fn set_int_attrib_layout(
+        &self, 
+        location: usize, 
+        n_elements: usize, 
+        stride: usize, 
+        byte_offset: usize, 
+        type_: crate::Type,
+    ) {
+        if !type_.is_integer() {
+            panic!("Only integer types can be used as integer attributes");
+        }
+        self.set_attrib(Attrib {
+            buffer: bound_vertex_buffer(),
+            location, 
+            n_elements, 
+            stride, 
+            byte_offset, 
+            type_: type_.to_gl_type_enum(), 
+            normalized: false, 
+            integer: true,
+        });
+    }
+
+    // sets it on the bound vertex array, the ones of other contexts follow on their next bind
+    fn set_attrib(&self, attrib: Attrib) {
+        apply(&attrib);
+        let mut attribs = self.attribs.borrow_mut();
+        match attribs.iter().position(|a| a.location == attrib.location) {
+            Some(i) => attribs[i] = attrib,
+            None => attribs.push(attrib),
+        }
+        let version = self.version.get() + 1;
+        self.version.set(version);
+        let context = current_context();
+        if let Some(vao) = self.vaos.borrow_mut().iter_mut().find(|v| v.context == context) {
+            vao.version = Some(version);
         }
     }
 }
 
+// the vertex arrays of other contexts can not be deleted from here, they go when their window closes
 impl Drop for VertexArrayObject {
     fn drop(&mut self) {
-        unsafe { gl::DeleteVertexArrays(1, &self.vao as *const GLuint) }
+        let context = current_context();
+        for vao in self.vaos.borrow().iter().filter(|v| v.context == context) {
+            unsafe { gl::DeleteVertexArrays(1, &vao.vao as *const GLuint) }
+        }
+    }
+}
+
+fn gen_vao() -> u32 {
+    let mut vao: u32 = 0;
+    unsafe { gl::GenVertexArrays(1, &mut vao); };
+    vao
+}
+
+fn bound_vertex_buffer() -> u32 {
+    let mut buffer: GLint = 0;
+    unsafe { gl::GetIntegerv(gl::ARRAY_BUFFER_BINDING, &mut buffer); }
+    buffer as u32
+}
+
+fn apply(a: &Attrib) {
+    unsafe {
+        gl::BindBuffer(gl::ARRAY_BUFFER, a.buffer);
+        gl::EnableVertexAttribArray(a.location as GLuint);
+        if a.integer {
+            gl::VertexAttribIPointer(
+                a.location as GLuint, 
+                a.n_elements as GLint, 
+                a.type_, 
+                a.stride as GLsizei, 
+                a.byte_offset as *const GLvoid
+            );
+        } else {
+            gl::VertexAttribPointer(
+                a.location as GLuint, 
+                a.n_elements as GLint, 
+                a.type_, 
+                a.normalized as GLboolean, 
+                a.stride as GLsizei, 
+                a.byte_offset as *const GLvoid
+            );
+        }
     }
 }
 
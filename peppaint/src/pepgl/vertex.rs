@@ -9,7 +9,11 @@ pub struct AttributeLayout {
     pub location: usize, 
     pub n_elements: usize,
     pub byte_offset: usize,
-    pub type_: crate::Type
+    pub type_: crate::Type,
+    // map fixed point values to [0, 1] (unsigned) or [-1, 1] (signed)
+    pub normalized: bool,
+    // keep ints as ints in the shader (ivec/uvec inputs), ignores normalized
+    pub integer: bool
 }
 
 pub trait Vertex { 
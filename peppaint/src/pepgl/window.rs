@@ -1,45 +1,110 @@
 use glfw::ffi::*;
 use gl;
 use std::os::raw::*;
+use std::cell::{ Cell, RefCell };
+use std::sync::Once;
+use crate::{ Key, MouseButton, Event, WindowConfig, WindowMode, raw_monitor, video_mode };
 
 pub type Screen = Window;
 
+thread_local! {
+    // the logical size of the window whose context is current
+    static SCREEN_SIZE: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+    // the id of the window whose context is current, ids are never reused
+    static CURRENT_CONTEXT: Cell<usize> = const { Cell::new(0) };
+    static NEXT_CONTEXT: Cell<usize> = const { Cell::new(1) };
+}
+
+// for things that follow the screen without access to the window, like the Painter
+pub fn get_screen_size() -> (usize, usize) {
+    SCREEN_SIZE.with(|s| s.get())
+}
+
+// for the GL objects that are not shared between windows, like vertex arrays
+pub fn current_context() -> usize {
+    CURRENT_CONTEXT.with(|c| c.get())
+}
+
 pub struct Window {
     window: *mut GLFWwindow,
+    // in screen coordinates, which the cursor uses too
     width: usize,
-    height: usize
+    height: usize,
+    // in pixels, larger than the size on HiDPI displays
+    framebuffer_width: usize,
+    framebuffer_height: usize,
+    content_scale: (f32, f32),
+    // position and size to return to when leaving fullscreen
+    windowed: (i32, i32, usize, usize),
+    // filled by the callbacks while polling, boxed so the user pointer stays valid
+    events: Box<RefCell<Vec<Event>>>,
+    context: usize,
 }
 
 impl Window {
     pub fn set_up(width: usize, height: usize, title: &str) -> Self {
+        Self::from_config(&WindowConfig::new(width, height, title))
+    }
+
+    pub fn from_config(config: &WindowConfig) -> Self {
+        Self::create(config, std::ptr::null_mut())
+    }
+
+    // shares textures, buffers and shaders with the other window; OpenGL does not
+    // share vertex arrays and framebuffers, VertexArrayObject makes one per context
+    // but framebuffers can only be drawn into in the window they were made in
+    pub fn shared(config: &WindowConfig, other: &Window) -> Self {
+        Self::create(config, other.window)
+    }
+
+    fn create(config: &WindowConfig, share: *mut GLFWwindow) -> Self {
+
+        init_glfw();
 
         unsafe {
 
-            if glfwInit() == FALSE {
-                panic!("Could not set up Window!");
-            }
+            config.set_hints();
 
-            glfwWindowHint(OPENGL_PROFILE, OPENGL_CORE_PROFILE);
-            glfwWindowHint(CONTEXT_VERSION_MAJOR, crate::GL_MAJOR as i32);
-            glfwWindowHint(CONTEXT_VERSION_MINOR, crate::GL_MINOR as i32);
-            glfwWindowHint(OPENGL_FORWARD_COMPAT, gl::TRUE as i32);
-            // glfwWindowHint(SAMPLES, 4);
-    
-            glfwSetErrorCallback(Some(on_error));
+            let (mut width, mut height) = (config.width, config.height);
+            let mut monitor = std::ptr::null_mut();
+            let mut position = None;
+            match config.mode {
+                WindowMode::Windowed => {}
+                WindowMode::Fullscreen { monitor: index } => {
+                    monitor = raw_monitor(index);
+                    let (w, h, refresh_rate) = video_mode(monitor);
+                    glfwWindowHint(REFRESH_RATE, refresh_rate);
+                    width = w;
+                    height = h;
+                }
+                WindowMode::Borderless { monitor: index } => {
+                    let m = raw_monitor(index);
+                    let (w, h, _) = video_mode(m);
+                    let (mut x, mut y) = (0, 0);
+                    glfwGetMonitorPos(m, &mut x, &mut y);
+                    position = Some((x, y));
+                    width = w;
+                    height = h;
+                }
+            }
     
             let window: *mut GLFWwindow = glfwCreateWindow(
                 width as c_int, 
                 height as c_int, 
-                const_char_ptr!(title), 
-                std::ptr::null_mut(), 
-                std::ptr::null_mut()
+                const_char_ptr!(config.title.as_str()), 
+                monitor, 
+                share
             );
 
             if window.is_null() {
                 panic!("Window could not be made!");
             }
+            if let Some((x, y)) = position {
+                glfwSetWindowPos(window, x, y);
+            }
 
             glfwMakeContextCurrent(window);
+            glfwSwapInterval(config.swap_interval);
 
             gl::load_with(|s| glfwGetProcAddress(const_char_ptr!(s)) as *const std::os::raw::c_void);
 
@@ -48,21 +113,69 @@ impl Window {
             gl::DepthFunc(gl::LESS);  
             gl::Enable(gl::BLEND); 
             gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA); 
-            // gl::Enable(gl::MULTISAMPLE);
+            if config.samples > 0 {
+                gl::Enable(gl::MULTISAMPLE);
+            }
+            if config.srgb {
+                gl::Enable(gl::FRAMEBUFFER_SRGB);
+            }
 
-            let this = Self { 
+            let events = Box::new(RefCell::new(vec![]));
+            glfwSetWindowUserPointer(window, &*events as *const RefCell<Vec<Event>> as *mut c_void);
+            crate::input::set_callbacks(window);
+
+            let mut this = Self { 
                 window,
                 width,
-                height
+                height,
+                framebuffer_width: width,
+                framebuffer_height: height,
+                content_scale: (1.0, 1.0),
+                windowed: (100, 100, config.width, config.height),
+                events,
+                context: NEXT_CONTEXT.with(|n| n.replace(n.get() + 1)),
             };
 
             this.make_currect();
+            this.query_sizes();
             this
         }
     }
 
     pub fn get_width(&self) -> usize { self.width }
     pub fn get_height(&self) -> usize { self.height }
+    pub fn get_framebuffer_width(&self) -> usize { self.framebuffer_width }
+    pub fn get_framebuffer_height(&self) -> usize { self.framebuffer_height }
+    pub fn get_content_scale(&self) -> (f32, f32) { self.content_scale }
+    // pixels per screen coordinate
+    pub fn get_pixel_ratio(&self) -> f32 {
+        if self.width == 0 { 1.0 } else { self.framebuffer_width as f32 / self.width as f32 }
+    }
+
+    // asks GLFW for all sizes and sets the viewport to the framebuffer
+    fn query_sizes(&mut self) {
+        let (mut w, mut h) = (0, 0);
+        let (mut sx, mut sy) = (1.0, 1.0);
+        unsafe {
+            glfwGetWindowSize(self.window, &mut w, &mut h);
+            self.width = w.max(0) as usize;
+            self.height = h.max(0) as usize;
+            glfwGetFramebufferSize(self.window, &mut w, &mut h);
+            self.framebuffer_width = w.max(0) as usize;
+            self.framebuffer_height = h.max(0) as usize;
+            glfwGetWindowContentScale(self.window, &mut sx, &mut sy);
+        }
+        self.content_scale = (sx, sy);
+        // the viewport belongs to the context, other windows set theirs when made current
+        if self.is_current() {
+            self.update_viewport();
+            SCREEN_SIZE.with(|s| s.set((self.width, self.height)));
+        }
+    }
+
+    pub fn update_viewport(&self) {
+        unsafe { gl::Viewport(0, 0, self.framebuffer_width as c_int, self.framebuffer_height as c_int); }
+    }
 
     pub fn set_background(&self, r: f32, g: f32, b: f32) {
         unsafe { gl::ClearColor(r, g, b, 1.0); }
@@ -70,8 +183,88 @@ impl Window {
 
     pub fn set_size(&mut self, w: usize, h: usize) {
         unsafe { glfwSetWindowSize(self.window, w as c_int, h as c_int) }
-        self.width = w;
-        self.height = h;
+        self.query_sizes();
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        unsafe { !glfwGetWindowMonitor(self.window).is_null() }
+    }
+
+    // exclusive fullscreen on a monitor with its current video mode
+    pub fn set_fullscreen(&mut self, monitor: usize) {
+        if !self.is_fullscreen() {
+            self.remember_windowed();
+        }
+        unsafe {
+            let monitor = raw_monitor(monitor);
+            let (w, h, refresh_rate) = video_mode(monitor);
+            glfwSetWindowMonitor(self.window, monitor, 0, 0, w as c_int, h as c_int, refresh_rate);
+        }
+        self.query_sizes();
+    }
+
+    // an undecorated window covering a monitor
+    pub fn set_borderless(&mut self, monitor: usize) {
+        if !self.is_fullscreen() {
+            self.remember_windowed();
+        }
+        unsafe {
+            let monitor = raw_monitor(monitor);
+            let (w, h, _) = video_mode(monitor);
+            let (mut x, mut y) = (0, 0);
+            glfwGetMonitorPos(monitor, &mut x, &mut y);
+            glfwSetWindowAttrib(self.window, DECORATED, FALSE);
+            glfwSetWindowMonitor(self.window, std::ptr::null_mut(), x, y, w as c_int, h as c_int, DONT_CARE);
+        }
+        self.query_sizes();
+    }
+
+    // back to where the window was before going fullscreen or borderless
+    pub fn set_windowed(&mut self) {
+        let (x, y, w, h) = self.windowed;
+        unsafe {
+            glfwSetWindowAttrib(self.window, DECORATED, TRUE);
+            glfwSetWindowMonitor(self.window, std::ptr::null_mut(), x, y, w as c_int, h as c_int, DONT_CARE);
+        }
+        self.query_sizes();
+    }
+
+    // between windowed and fullscreen on the monitor the window is on the most
+    pub fn toggle_fullscreen(&mut self) {
+        if self.is_fullscreen() {
+            self.set_windowed();
+        } else {
+            let monitor = self.get_monitor_index();
+            self.set_fullscreen(monitor);
+        }
+    }
+
+    // the monitor that contains the center of the window
+    pub fn get_monitor_index(&self) -> usize {
+        let (mut x, mut y) = (0, 0);
+        unsafe { glfwGetWindowPos(self.window, &mut x, &mut y); }
+        let cx = x + self.width as i32 / 2;
+        let cy = y + self.height as i32 / 2;
+        crate::get_monitors().iter()
+            .find(|m| cx >= m.x && cy >= m.y && cx < m.x + m.width as i32 && cy < m.y + m.height as i32)
+            .map(|m| m.index)
+            .unwrap_or(0)
+    }
+
+    fn remember_windowed(&mut self) {
+        let (mut x, mut y) = (0, 0);
+        unsafe { glfwGetWindowPos(self.window, &mut x, &mut y); }
+        self.windowed = (x, y, self.width, self.height);
+    }
+
+    pub fn set_vsync(&self, vsync: bool) {
+        self.set_swap_interval(vsync as i32);
+    }
+
+    // applies to the current context
+    pub fn set_swap_interval(&self, interval: i32) {
+        self.make_currect();
+        unsafe { glfwSwapInterval(interval); }
     }
 
     pub fn set_title(&self, title: &str) {
@@ -85,6 +278,11 @@ impl Window {
         }
     }
 
+    // without polling, for when the events are polled elsewhere
+    pub fn is_close_requested(&self) -> bool {
+        unsafe { glfwWindowShouldClose(self.window) == TRUE }
+    }
+
     pub fn should_close(&self) -> bool {  
         unsafe { 
             glfwPollEvents();
@@ -92,19 +290,47 @@ impl Window {
         } 
     }
 
+    pub fn poll_events(&self) {
+        unsafe { glfwPollEvents(); }
+    }
+
+    // the events since the last call, keeps the sizes and the viewport up to date
+    pub fn take_events(&mut self) -> Vec<Event> {
+        let events = std::mem::take(&mut *self.events.borrow_mut());
+        let resized = events.iter().any(|e| matches!(e,
+            Event::Resized(..) | Event::FramebufferResized(..) | Event::ContentScaleChanged(..)));
+        if resized {
+            self.query_sizes();
+        }
+        events
+    }
+
     pub fn make_currect(&self) {
         unsafe { glfwMakeContextCurrent(self.window); }
+        CURRENT_CONTEXT.with(|c| c.set(self.context));
+        self.update_viewport();
+        SCREEN_SIZE.with(|s| s.set((self.width, self.height)));
+    }
+
+    pub fn is_current(&self) -> bool {
+        unsafe { glfwGetCurrentContext() == self.window }
     }
 
     pub fn is_key_pressed(&self, key: Key) -> bool {
+        // KEY_UNKNOWN is not a valid key for glfwGetKey
+        if key == Key::Unknown { return false }
         unsafe {
             glfwGetKey(self.window, key.to_glfw()) == PRESS
         }
     }
 
     pub fn is_mouse_presses(&self) -> bool {
+        self.is_mouse_button_pressed(MouseButton::Left)
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
         unsafe {
-            glfwGetMouseButton(self.window, MOUSE_BUTTON_LEFT) == PRESS
+            glfwGetMouseButton(self.window, button.to_glfw()) == PRESS
         }
     }
 
@@ -120,38 +346,25 @@ impl Window {
 
 impl Drop for Window {
     fn drop(&mut self) {
-        unsafe { glfwDestroyWindow(self.window); }
+        unsafe { 
+            glfwSetWindowUserPointer(self.window, std::ptr::null_mut());
+            glfwDestroyWindow(self.window); 
+        }
     }
 }
 
-extern "C" fn on_error(error: c_int, des: *const c_char) {
-    println!("Error {}: {:?}", error, des);
-}
+static GLFW_INIT: Once = Once::new();
 
-pub enum Key {
-    Up,
-    Down,
-    Left,
-    Right,
-    Escape,
-    Space,
-    A, W, D, S
+// once for all windows, GLFW is never terminated
+fn init_glfw() {
+    GLFW_INIT.call_once(|| unsafe {
+        glfwSetErrorCallback(Some(on_error));
+        if glfwInit() == FALSE {
+            panic!("Could not set up Window!");
+        }
+    });
 }
 
-impl Key {
-    fn to_glfw(&self) -> c_int {
-        use Key::*;
-        match self {
-            Up => KEY_UP,
-            Down => KEY_DOWN,
-            Left => KEY_LEFT,
-            Right => KEY_RIGHT,
-            Escape => KEY_ESCAPE,
-            Space => KEY_SPACE,
-            A => KEY_A,
-            W => KEY_W,
-            D => KEY_D,
-            S => KEY_S
-        }
-    }
+extern "C" fn on_error(error: c_int, des: *const c_char) {
+    println!("Error {}: {:?}", error, des);
 }
@@ -0,0 +1,204 @@
+use glfw::ffi::*;
+use std::os::raw::*;
+use std::ffi::CStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowMode {
+    Windowed,
+    // takes over the monitor with its current video mode
+    Fullscreen { monitor: usize },
+    // an undecorated window covering the monitor, switching to it is instant and
+    // other windows can stay on top, which suits projectors
+    Borderless { monitor: usize },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GlProfile {
+    Core,
+    Compatibility,
+}
+
+// how Window::from_config makes the window, used as a builder:
+// WindowConfig::new(800, 600, "Gallery").fullscreen(1).vsync(true).samples(4)
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    pub width: usize,
+    pub height: usize,
+    pub title: String,
+    pub mode: WindowMode,
+    pub resizable: bool,
+    pub decorated: bool,
+    // frames to wait before swapping, 1 is vsync and 0 swaps immediately
+    pub swap_interval: i32,
+    // 0 for no multisampling
+    pub samples: u32,
+    pub srgb: bool,
+    pub gl_version: (u32, u32),
+    pub gl_profile: GlProfile,
+}
+
+impl WindowConfig {
+
+    pub fn new(width: usize, height: usize, title: &str) -> Self {
+        Self {
+            width,
+            height,
+            title: title.to_string(),
+            mode: WindowMode::Windowed,
+            resizable: true,
+            decorated: true,
+            swap_interval: 1,
+            samples: 0,
+            srgb: false,
+            gl_version: (crate::GL_MAJOR as u32, crate::GL_MINOR as u32),
+            gl_profile: GlProfile::Core,
+        }
+    }
+
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn windowed(mut self) -> Self {
+        self.mode = WindowMode::Windowed;
+        self
+    }
+
+    // monitor 0 is the primary one, see get_monitors
+    pub fn fullscreen(mut self, monitor: usize) -> Self {
+        self.mode = WindowMode::Fullscreen { monitor };
+        self
+    }
+
+    pub fn borderless(mut self, monitor: usize) -> Self {
+        self.mode = WindowMode::Borderless { monitor };
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn decorated(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        self
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.swap_interval = vsync as i32;
+        self
+    }
+
+    pub fn swap_interval(mut self, interval: i32) -> Self {
+        self.swap_interval = interval;
+        self
+    }
+
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    pub fn gl_version(mut self, major: u32, minor: u32) -> Self {
+        self.gl_version = (major, minor);
+        self
+    }
+
+    pub fn gl_profile(mut self, profile: GlProfile) -> Self {
+        self.gl_profile = profile;
+        self
+    }
+
+    pub(crate) unsafe fn set_hints(&self) {
+        glfwDefaultWindowHints();
+        let profile = match self.gl_profile {
+            GlProfile::Core => OPENGL_CORE_PROFILE,
+            GlProfile::Compatibility => OPENGL_COMPAT_PROFILE,
+        };
+        // profiles only exist from 3.2 on
+        if self.gl_version >= (3, 2) {
+            glfwWindowHint(OPENGL_PROFILE, profile);
+        }
+        glfwWindowHint(CONTEXT_VERSION_MAJOR, self.gl_version.0 as c_int);
+        glfwWindowHint(CONTEXT_VERSION_MINOR, self.gl_version.1 as c_int);
+        let forward_compat = self.gl_profile == GlProfile::Core && self.gl_version >= (3, 0);
+        glfwWindowHint(OPENGL_FORWARD_COMPAT, forward_compat as c_int);
+        glfwWindowHint(SAMPLES, self.samples as c_int);
+        glfwWindowHint(SRGB_CAPABLE, self.srgb as c_int);
+        glfwWindowHint(RESIZABLE, self.resizable as c_int);
+        let borderless = matches!(self.mode, WindowMode::Borderless { .. });
+        glfwWindowHint(DECORATED, (self.decorated && !borderless) as c_int);
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self { Self::new(500, 500, "PepMovie") }
+}
+
+#[derive(Clone, Debug)]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: String,
+    // of the desktop, in screen coordinates
+    pub x: i32,
+    pub y: i32,
+    pub width: usize,
+    pub height: usize,
+    pub refresh_rate: i32,
+}
+
+// the connected monitors, the primary one first; GLFW has to be initialized,
+// which is the case once a window exists
+pub fn get_monitors() -> Vec<MonitorInfo> {
+    raw_monitors().into_iter().enumerate().map(|(index, monitor)| unsafe {
+        let (mut x, mut y) = (0, 0);
+        glfwGetMonitorPos(monitor, &mut x, &mut y);
+        let (width, height, refresh_rate) = video_mode(monitor);
+        let name = glfwGetMonitorName(monitor);
+        let name = if name.is_null() { String::new() } else { CStr::from_ptr(name).to_string_lossy().into_owned() };
+        MonitorInfo { index, name, x, y, width, height, refresh_rate }
+    }).collect()
+}
+
+pub(crate) fn raw_monitors() -> Vec<*mut GLFWmonitor> {
+    unsafe {
+        let mut count = 0;
+        let monitors = glfwGetMonitors(&mut count);
+        if monitors.is_null() || count <= 0 { return vec![] }
+        std::slice::from_raw_parts(monitors, count as usize).to_vec()
+    }
+}
+
+// falls back to the primary monitor
+pub(crate) fn raw_monitor(index: usize) -> *mut GLFWmonitor {
+    match raw_monitors().get(index) {
+        Some(monitor) => *monitor,
+        None => {
+            println!("There is no monitor {}, using the primary one", index);
+            unsafe { glfwGetPrimaryMonitor() }
+        }
+    }
+}
+
+pub(crate) fn video_mode(monitor: *mut GLFWmonitor) -> (usize, usize, i32) {
+    unsafe {
+        let mode = glfwGetVideoMode(monitor);
+        match mode.as_ref() {
+            Some(mode) => (mode.width.max(0) as usize, mode.height.max(0) as usize, mode.refreshRate),
+            None => (0, 0, DONT_CARE),
+        }
+    }
+}
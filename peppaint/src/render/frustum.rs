@@ -0,0 +1,81 @@
+use crate::{ Bounds, Aabb, BoundingSphere, Mat4 };
+
+use nalgebra_glm as glm;
+
+// the six planes of a view projection, with normals pointing inwards
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [glm::Vec4; 6],
+}
+
+impl Frustum {
+
+    pub fn from_vp(vp: &glm::Mat4) -> Self {
+        let row = |i: usize| glm::vec4(vp[(i, 0)], vp[(i, 1)], vp[(i, 2)], vp[(i, 3)]);
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let mut planes = [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r3 + r2, r3 - r2];
+        for p in planes.iter_mut() {
+            *p /= glm::length(&glm::vec3(p.x, p.y, p.z));
+        }
+        Self { planes }
+    }
+
+    // for the view projections of OrthoCam and HeadingProjCam
+    pub fn from_mat4(vp: &Mat4) -> Self {
+        Self::from_vp(&vp.to_glm())
+    }
+
+    fn distance(plane: &glm::Vec4, p: &glm::Vec3) -> f32 {
+        plane.x * p.x + plane.y * p.y + plane.z * p.z + plane.w
+    }
+
+    pub fn contains_point(&self, p: &glm::Vec3) -> bool {
+        self.planes.iter().all(|plane| Self::distance(plane, p) >= 0.0)
+    }
+
+    pub fn intersects_sphere(&self, s: &BoundingSphere) -> bool {
+        self.planes.iter().all(|plane| Self::distance(plane, &s.center) >= -s.radius)
+    }
+
+    // only tests the corner furthest along each plane normal, so boxes near
+    // the frustum corners can pass while being outside
+    pub fn intersects_aabb(&self, b: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            let p = glm::vec3(
+                if plane.x >= 0.0 { b.max.x } else { b.min.x },
+                if plane.y >= 0.0 { b.max.y } else { b.min.y },
+                if plane.z >= 0.0 { b.max.z } else { b.min.z },
+            );
+            Self::distance(plane, &p) >= 0.0
+        })
+    }
+
+    // the sphere test goes first since it is the cheapest
+    pub fn intersects(&self, bounds: &Bounds, model: &glm::Mat4) -> bool {
+        let world = bounds.transformed(model);
+        self.intersects_sphere(&world.sphere) && self.intersects_aabb(&world.aabb)
+    }
+}
+
+// how many objects were drawn and skipped since the last reset
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CullStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
+
+impl CullStats {
+
+    pub fn reset(&mut self) {
+        self.drawn = 0;
+        self.culled = 0;
+    }
+
+    // counts the object and returns whether it should be drawn, objects
+    // without bounds are always drawn
+    pub fn test(&mut self, frustum: &Frustum, bounds: Option<&Bounds>, model: &glm::Mat4) -> bool {
+        let visible = bounds.is_none_or(|b| frustum.intersects(b, model));
+        if visible { self.drawn += 1 } else { self.culled += 1 }
+        visible
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    ShaderProgram,
+    ShadowSettings,
+    ShadowCaster,
+};
+
+use nalgebra_glm as glm;
+
+// size of the uLights array in the material shaders, must match MAX_LIGHTS there
+pub const MAX_LIGHTS: usize = 16;
+
+const DIRECTIONAL: i32 = 0;
+const POINT: i32 = 1;
+const SPOT: i32 = 2;
+
+// None for zero length or NaN directions, glm::normalize would make them NaN
+pub(crate) fn unit(v: &glm::Vec3) -> Option<glm::Vec3> {
+    let length = glm::length(v);
+    if length > 1e-6 && length.is_finite() { Some(v / length) } else { None }
+}
+
+// light intensity is divided by constant + linear * d + quadratic * d^2
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl Attenuation {
+    pub fn none() -> Self {
+        Self { constant: 1.0, linear: 0.0, quadratic: 0.0 }
+    }
+
+    pub fn new(constant: f32, linear: f32, quadratic: f32) -> Self {
+        Self { constant, linear, quadratic }
+    }
+
+    // falls off to roughly nothing at the given distance
+    pub fn range(range: f32) -> Self {
+        Self { constant: 1.0, linear: 4.5 / range, quadratic: 75.0 / (range * range) }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DirectionalLight {
+    pub direction: glm::Vec3,
+    pub color: glm::Vec3,
+    pub intensity: f32,
+    pub shadow: Option<ShadowSettings>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PointLight {
+    pub position: glm::Vec3,
+    pub color: glm::Vec3,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SpotLight {
+    pub position: glm::Vec3,
+    pub direction: glm::Vec3,
+    pub color: glm::Vec3,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+    // half angles of the cone in radians, the light fades out between them
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+    // a headlight is moved to the location and heading of the context every frame
+    pub headlight: bool,
+    pub shadow: Option<ShadowSettings>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Light {
+    Directional(DirectionalLight),
+    Point(PointLight),
+    Spot(SpotLight),
+}
+
+impl Light {
+
+    pub fn directional(direction: glm::Vec3, color: glm::Vec3) -> Self {
+        Light::Directional(DirectionalLight { direction, color, intensity: 1.0, shadow: None })
+    }
+
+    pub fn point(position: glm::Vec3, color: glm::Vec3, range: f32) -> Self {
+        Light::Point(PointLight { 
+            position, 
+            color, 
+            intensity: 1.0, 
+            attenuation: Attenuation::range(range) 
+        })
+    }
+
+    pub fn spot(position: glm::Vec3, direction: glm::Vec3, color: glm::Vec3, angle: f32, range: f32) -> Self {
+        Light::Spot(SpotLight {
+            position,
+            direction,
+            color,
+            intensity: 1.0,
+            attenuation: Attenuation::range(range),
+            inner_angle: angle * 0.8,
+            outer_angle: angle,
+            headlight: false,
+            shadow: None,
+        })
+    }
+
+    // a spot light that follows MyContext::get_location and get_heading
+    pub fn headlight(color: glm::Vec3, angle: f32, range: f32) -> Self {
+        let mut light = Self::spot(glm::vec3(0.0, 0.0, 0.0), -glm::Vec3::z(), color, angle, range);
+        if let Light::Spot(spot) = &mut light { spot.headlight = true; }
+        light
+    }
+
+    // point lights do not cast shadows
+    pub fn with_shadows(mut self, settings: ShadowSettings) -> Self {
+        match &mut self {
+            Light::Directional(l) => l.shadow = Some(settings),
+            Light::Spot(l) => l.shadow = Some(settings),
+            Light::Point(_) => println!("Point lights can not cast shadows"),
+        }
+        self
+    }
+
+    pub fn position(&self) -> Option<glm::Vec3> {
+        match self {
+            Light::Directional(_) => None,
+            Light::Point(l) => Some(l.position),
+            Light::Spot(l) => Some(l.position),
+        }
+    }
+
+    fn upload(&self, program: &mut ShaderProgram, i: usize, shadow: Option<&ShadowCaster>) {
+        let name = |field: &str| format!("uLights[{}].{}", i, field);
+        let settings = match self {
+            Light::Directional(l) => l.shadow,
+            Light::Spot(l) => l.shadow,
+            Light::Point(_) => None,
+        };
+        match (shadow, settings) {
+            (Some(caster), Some(settings)) => {
+                program.set_int(&name("shadow"), caster.first_map as i32);
+                program.set_int(&name("cascades"), caster.n_maps as i32);
+                program.set_float(&name("bias"), settings.bias);
+                program.set_float(&name("slopeBias"), settings.slope_bias);
+                program.set_int(&name("pcf"), settings.pcf_radius);
+            },
+            _ => program.set_int(&name("shadow"), -1)
+        }
+
+        let attenuation = |a: &Attenuation| glm::vec3(a.constant, a.linear, a.quadratic);
+        match self {
+            Light::Directional(l) => {
+                program.set_int(&name("type"), DIRECTIONAL);
+                program.set_vec3f(&name("direction"), &unit(&l.direction).unwrap_or(-glm::Vec3::z()));
+                program.set_vec3f(&name("color"), &(l.color * l.intensity));
+            },
+            Light::Point(l) => {
+                program.set_int(&name("type"), POINT);
+                program.set_vec3f(&name("position"), &l.position);
+                program.set_vec3f(&name("color"), &(l.color * l.intensity));
+                program.set_vec3f(&name("attenuation"), &attenuation(&l.attenuation));
+            },
+            Light::Spot(l) => {
+                program.set_int(&name("type"), SPOT);
+                program.set_vec3f(&name("position"), &l.position);
+                program.set_vec3f(&name("direction"), &unit(&l.direction).unwrap_or(-glm::Vec3::z()));
+                program.set_vec3f(&name("color"), &(l.color * l.intensity));
+                program.set_vec3f(&name("attenuation"), &attenuation(&l.attenuation));
+                program.set_float(&name("innerCos"), l.inner_angle.cos());
+                program.set_float(&name("outerCos"), l.outer_angle.cos());
+            }
+        }
+    }
+}
+
+// all lights of a scene, registered on MyContext
+pub struct Lights {
+    lights: Vec<Option<Light>>,
+    pub ambient: glm::Vec3,
+    max_lights: usize,
+}
+
+impl Lights {
+
+    pub fn ne() -> Self {
+        Self {
+            lights: Vec::new(),
+            ambient: glm::vec3(0.1, 0.1, 0.1),
+            max_lights: MAX_LIGHTS,
+        }
+    }
+
+    // returns the id to get or remove the light with
+    pub fn add(&mut self, light: Light) -> usize {
+        match self.lights.iter().position(|l| l.is_none()) {
+            Some(id) => { self.lights[id] = Some(light); id },
+            None => { self.lights.push(Some(light)); self.lights.len() - 1 }
+        }
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Light> {
+        self.lights.get_mut(id)?.take()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Light> { self.lights.get(id)?.as_ref() }
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Light> { self.lights.get_mut(id)?.as_mut() }
+    pub fn clear(&mut self) { self.lights.clear() }
+    pub fn len(&self) -> usize { self.lights.iter().flatten().count() }
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+    pub fn iter(&self) -> impl Iterator<Item = &Light> { self.lights.iter().flatten() }
+    pub fn iter_with_ids(&self) -> impl Iterator<Item = (usize, &Light)> {
+        self.lights.iter().enumerate().filter_map(|(id, l)| Some((id, l.as_ref()?)))
+    }
+
+    pub fn set_ambient(&mut self, r: f32, g: f32, b: f32) { self.ambient = glm::vec3(r, g, b) }
+    pub fn get_max_lights(&self) -> usize { self.max_lights }
+    pub fn set_max_lights(&mut self, max: usize) {
+        if max > MAX_LIGHTS {
+            println!("At most {} lights are supported by the shaders", MAX_LIGHTS);
+        }
+        self.max_lights = max.min(MAX_LIGHTS);
+    }
+
+    pub fn follow(&mut self, position: &glm::Vec3, heading: &glm::Vec3) {
+        let heading = match unit(heading) {
+            Some(h) => h,
+            None => return
+        };
+        for light in self.lights.iter_mut().flatten() {
+            if let Light::Spot(spot) = light {
+                if spot.headlight {
+                    spot.position = *position;
+                    spot.direction = heading;
+                }
+            }
+        }
+    }
+
+    // when there are more lights than fit, directional lights go first and the
+    // rest are picked nearest to the camera
+    pub fn upload(&self, program: &mut ShaderProgram, camera: &glm::Vec3, shadows: &[ShadowCaster]) {
+        let mut lights: Vec<(usize, &Light)> = self.iter_with_ids().collect();
+        if lights.len() > self.max_lights {
+            let distance = |(_, l): &(usize, &Light)| match l.position() {
+                Some(p) => glm::distance2(&p, camera),
+                None => -1.0
+            };
+            lights.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+            lights.truncate(self.max_lights);
+        }
+
+        program.bind();
+        program.set_vec3f("uAmbientLight", &self.ambient);
+        program.set_int("uNLights", lights.len() as i32);
+        for (i, (id, light)) in lights.iter().enumerate() {
+            light.upload(program, i, shadows.iter().find(|s| s.light == *id));
+        }
+    }
+}
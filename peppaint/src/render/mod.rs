@@ -4,4 +4,24 @@ pub use painter::Painter;
 pub mod painter_depricated;
 pub use painter_depricated::PainterDepricated;
 
-pub mod transform_stack_2d;
\ No newline at end of file
+pub mod transform_stack_2d;
+
+pub mod pixel_font;
+
+pub mod renderer;
+pub use renderer::{ Renderer, Drawable };
+
+pub mod light;
+pub use light::*;
+
+pub mod shadow;
+pub use shadow::*;
+
+pub mod scene;
+pub use scene::*;
+
+pub mod frustum;
+pub use frustum::*;
+
+pub mod stats;
+pub use stats::*;
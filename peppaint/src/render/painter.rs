@@ -1,10 +1,12 @@
 use crate::{
     Mesh,
     types::*,
-    std::PaintingVertex,
+    std::{ PaintingVertex, PosColorTex },
+    Texture,
     constants::TWO_PI,
     ShaderProgram,
-    transform_stack_2d::TransformStack2D
+    transform_stack_2d::TransformStack2D,
+    pixel_font,
 };
 
 use nalgebra_glm as glm;
@@ -21,8 +23,12 @@ pub struct Painter {
     painting: Mesh<PaintingVertex>,
     settings: PainterSettings,
     projection: glm::Mat4,
+    // the screen size the projection was made for, if it follows the screen
+    screen: Option<(usize, usize)>,
     transforms: TransformStack2D,
-    program: ShaderProgram
+    program: ShaderProgram,
+    image: Mesh<PosColorTex>,
+    image_program: ShaderProgram,
 } 
 
 impl Painter {
@@ -35,6 +41,12 @@ impl Painter {
             include_str!("../std/shaders/painting.vert")
         ).expect("Could not find Painter Shaders");
         painting.init_buffers();
+        let image = Mesh::dynamic_with_capacity(4, 6);
+        image.init_buffers();
+        let image_program = ShaderProgram::from_frag_and_vert_src(
+            include_str!("../std/shaders/painting_image.frag"), 
+            include_str!("../std/shaders/painting_image.vert")
+        ).expect("Could not find Painter image Shaders");
 
         let color = Color(1.0, 1.0, 1.0, 1.0);
         let projection = glm::identity();
@@ -50,9 +62,12 @@ impl Painter {
         Self { 
             painting,
             projection, 
+            screen: None,
             transforms,
             settings,
-            program
+            program,
+            image,
+            image_program,
         }
     }
 
@@ -69,12 +84,14 @@ impl Painter {
     }
 
     pub fn paint_default(&mut self) {
+        self.follow_screen();
         self.program.bind();
         self.program.set_mat4f_array_generic("uViewProjection", &[self.projection]);
         self.program.set_mat3f_array_generic("uModels", self.transforms.get_stack());
         self.program.set_int("doForeground", self.settings.foreground as i32);
         self.painting.subbuffer();
         self.painting.show();
+        crate::count_batch_flush();
         self.new_painting();
     }
 
@@ -133,12 +150,28 @@ impl Painter {
 
     // TRANSFORMS
 
+    // from then on the projection follows the size of the window
     pub fn adjust_to_screen(&mut self, w: usize, h: usize) {
         self.projection = glm::ortho(
             0.0, w as f32,
             h as f32, 0.0, 
             1.0, -1.0
         );
+        self.screen = Some((w, h));
+    }
+
+    pub fn fit_screen(&mut self) {
+        let (w, h) = crate::get_screen_size();
+        self.adjust_to_screen(w, h);
+    }
+
+    fn follow_screen(&mut self) {
+        if let Some(size) = self.screen {
+            let screen = crate::get_screen_size();
+            if screen != size && screen.0 > 0 && screen.1 > 0 {
+                self.adjust_to_screen(screen.0, screen.1);
+            }
+        }
     }
 
     pub fn new_model(&mut self) { 
@@ -314,6 +347,84 @@ impl Painter {
 
         self.fan(m.0, m.1, fan.as_slice());
     }
+
+    // IMAGES
+
+    // tinted by the current color
+    // TEXT
+
+    // with the pixel font, x and y are the top left and size is the size of a font pixel
+    pub fn text(&mut self, text: &str, x: Float, y: Float, size: Float) {
+        let mut x0 = x;
+        for c in text.chars() {
+            let rows = pixel_font::glyph(c);
+            for (row, line) in rows.iter().enumerate() {
+                // runs of pixels become one rect
+                let bytes = line.as_bytes();
+                let mut col = 0;
+                while col < bytes.len() {
+                    if bytes[col] != b'#' { col += 1; continue }
+                    let start = col;
+                    while col < bytes.len() && bytes[col] == b'#' { col += 1; }
+                    self.rect(
+                        x0 + start as Float * size, 
+                        y + row as Float * size, 
+                        (col - start) as Float * size, 
+                        size
+                    );
+                }
+            }
+            x0 += pixel_font::GLYPH_ADVANCE as Float * size;
+        }
+    }
+
+    pub fn text_width(text: &str, size: Float) -> Float {
+        let n = text.chars().count();
+        if n == 0 { return 0.0 }
+        (n * pixel_font::GLYPH_ADVANCE - 1) as Float * size
+    }
+
+    pub fn text_height(size: Float) -> Float {
+        pixel_font::GLYPH_HEIGHT as Float * size
+    }
+
+    pub fn image(&mut self, texture: &Texture, x: Float, y: Float, width: Float, height: Float) {
+        self.paint_image(texture, x, y, width, height, false);
+    }
+
+    // for depth textures like shadow maps, the red channel is shown in grey and the
+    // image is flipped since textures that are rendered to start at the bottom
+    pub fn depth_image(&mut self, texture: &Texture, x: Float, y: Float, width: Float, height: Float) {
+        self.paint_image(texture, x, y, width, height, true);
+    }
+
+    // images are not batched, the shapes so far are painted first so the image ends up on top
+    fn paint_image(&mut self, texture: &Texture, x: Float, y: Float, width: Float, height: Float, depth: bool) {
+        self.paint();
+        let m = self.transforms.get_stack()[self.transforms.get_current_index()];
+        let color = Color(self.settings.color.x, self.settings.color.y, self.settings.color.z, self.settings.alpha);
+        let (top, bottom) = if depth { (1.0, 0.0) } else { (0.0, 1.0) };
+        let vertex = |px: Float, py: Float, u: Float, v: Float| {
+            let p = m * glm::vec3(px, py, 1.0);
+            PosColorTex { pos: Vec3(p.x / p.z, p.y / p.z, 0.0), color, texcoords: Vec2(u, v) }
+        };
+        let v1 = vertex(x, y, 0.0, top);
+        let v2 = vertex(x + width, y, 1.0, top);
+        let v3 = vertex(x + width, y + height, 1.0, bottom);
+        let v4 = vertex(x, y + height, 0.0, bottom);
+        self.image.clear();
+        self.image.push_quad(&v1, &v2, &v3, &v4);
+
+        crate::gl_disable_depth();
+        texture.bind_to_unit(0);
+        self.image_program.bind();
+        self.image_program.set_mat4f_array_generic("uViewProjection", &[self.projection]);
+        self.image_program.set_int("uTexture", 0);
+        self.image_program.set_int("uDepth", depth as i32);
+        self.image.subbuffer();
+        self.image.show();
+        crate::gl_enable_depth();
+    }
 }
 
 struct PainterSettings { 
@@ -81,6 +81,7 @@ impl PainterDepricated {
         self.program.set_mat4f_array_depricated("uModels", &self.models, self.model_ptr + 1);
         self.painting.subbuffer();
         self.painting.show();
+        crate::count_batch_flush();
         self.new_painting();
     }
 
@@ -0,0 +1,190 @@
+use crate::{
+    Mesh,
+    Model,
+    Material,
+    Shading,
+    ShaderProgram,
+    Vertex,
+    Lights,
+    Shadows,
+    Painter,
+    Framebuffer,
+    Bounds,
+    Frustum,
+    CullStats,
+};
+
+use nalgebra_glm as glm;
+use std::fmt::Debug;
+
+// anything that can be drawn with a material once its program is bound
+pub trait Drawable {
+    fn draw(&self);
+    // in model space, drawables without bounds are never culled
+    fn bounds(&self) -> Option<Bounds> { None }
+}
+
+impl<T: Vertex + Copy + Debug> Drawable for Mesh<T> {
+    fn draw(&self) {
+        self.bind_vao();
+        self.show();
+    }
+    fn bounds(&self) -> Option<Bounds> { self.get_bounds() }
+}
+
+// draws meshes and models with the built-in material shaders, the meshes
+// should have a PosNormTex or PosNormTexTan layout
+pub struct Renderer {
+    unlit: ShaderProgram,
+    blinn_phong: ShaderProgram,
+    pbr: ShaderProgram,
+    shadows: Shadows,
+    // the light space matrix while drawing into a shadow map
+    depth_pass: Option<glm::Mat4>,
+    view_projection: glm::Mat4,
+    camera_position: glm::Vec3,
+    frustum: Frustum,
+    // of the camera pass, reset by set_camera
+    stats: CullStats,
+    pub culling: bool,
+}
+
+impl Renderer {
+
+    pub fn ne() -> Self {
+        Self {
+            unlit: crate::std::shaders::unlit(),
+            blinn_phong: crate::std::shaders::blinn_phong(),
+            pbr: crate::std::shaders::pbr(),
+            shadows: Shadows::ne(),
+            depth_pass: None,
+            view_projection: glm::identity(),
+            camera_position: glm::vec3(0.0, 0.0, 0.0),
+            frustum: Frustum::from_vp(&glm::identity()),
+            stats: CullStats::default(),
+            culling: true,
+        }
+    }
+
+    pub fn set_camera(&mut self, view_projection: &glm::Mat4, position: &glm::Vec3) {
+        self.view_projection = *view_projection;
+        self.camera_position = *position;
+        self.frustum = Frustum::from_vp(view_projection);
+        self.stats.reset();
+    }
+
+    pub fn get_stats(&self) -> CullStats { self.stats }
+
+    // shadow passes cull against the light frustum and are not counted
+    fn is_visible(&mut self, bounds: Option<Bounds>, model: &glm::Mat4) -> bool {
+        if !self.culling { return true }
+        match self.depth_pass {
+            Some(light_space) => bounds.is_none_or(|b| Frustum::from_vp(&light_space).intersects(&b, model)),
+            None => self.stats.test(&self.frustum, bounds.as_ref(), model)
+        }
+    }
+
+    // renders the shadow maps of all shadow casting lights, draw is called once
+    // per map and should draw everything that casts shadows with this renderer
+    pub fn render_shadows<F: FnMut(&mut Renderer)>(&mut self, lights: &Lights, mut draw: F) {
+        self.shadows.prepare(lights, &self.view_projection);
+        let viewport = crate::gl_get_viewport();
+        for i in 0..self.shadows.len() {
+            self.shadows.begin_map(i);
+            self.depth_pass = self.shadows.get_matrix(i).cloned();
+            draw(self);
+        }
+        self.depth_pass = None;
+        Framebuffer::bind_default();
+        crate::gl_set_viewport(viewport);
+    }
+
+    // the forward pass, uploads the lights and the shadows rendered this frame to
+    // the lit shaders, it should come after set_camera and render_shadows
+    pub fn set_lights(&mut self, lights: &Lights) {
+        for program in [&mut self.blinn_phong, &mut self.pbr].iter_mut() {
+            lights.upload(program, &self.camera_position, self.shadows.get_casters());
+            self.shadows.upload(program);
+        }
+    }
+
+    pub fn get_shadows(&self) -> &Shadows { &self.shadows }
+
+    pub fn program(&mut self, shading: Shading) -> &mut ShaderProgram {
+        match shading {
+            Shading::Unlit => &mut self.unlit,
+            Shading::BlinnPhong => &mut self.blinn_phong,
+            Shading::Pbr => &mut self.pbr,
+        }
+    }
+
+    // binds the program of the material and sets the per draw uniforms
+    fn prepare(&mut self, material: &Material, model: &glm::Mat4) {
+        if let Some(light_space) = self.depth_pass {
+            let program = self.shadows.get_program();
+            program.bind();
+            program.set_mat4f("uViewProjection", light_space.as_ptr());
+            program.set_mat4f("uModel", model.as_ptr());
+            return
+        }
+
+        let vp = self.view_projection;
+        let camera = self.camera_position;
+        let shading = material.shading;
+        if shading != Shading::Unlit {
+            self.shadows.bind_maps();
+        }
+        let program = self.program(shading);
+        program.bind();
+        program.set_mat4f("uViewProjection", vp.as_ptr());
+        program.set_mat4f("uModel", model.as_ptr());
+        if shading != Shading::Unlit {
+            program.set_vec3f("uCameraPosition", &camera);
+        }
+        material.bind(program);
+    }
+
+    pub fn draw_mesh<D: Drawable + ?Sized>(&mut self, mesh: &D, material: &Material, model: &glm::Mat4) {
+        if !self.is_visible(mesh.bounds(), model) { return }
+        self.prepare(material, model);
+        mesh.draw();
+    }
+
+    // every mesh is drawn with its own material, or Material::none if it has none
+    pub fn draw_model(&mut self, model: &Model, transform: &glm::Mat4) {
+        let none = Material::none();
+        let mut draw = |mesh: &crate::model::ModelMesh, world: &glm::Mat4| {
+            if !self.is_visible(mesh.bounds, world) { return }
+            let material = match mesh.material {
+                Some(i) => &model.materials[i],
+                None => &none
+            };
+            self.prepare(material, world);
+            mesh.vao.bind();
+            crate::gl_draw_tris(mesh.n_indices);
+        };
+
+        if model.nodes.is_empty() {
+            for mesh in model.meshes.iter() {
+                draw(mesh, transform);
+            }
+            return
+        }
+        let worlds = model.world_transforms();
+        for (node, world) in model.nodes.iter().zip(worlds.iter()) {
+            let world = transform * world;
+            for mesh in node.meshes.iter() {
+                draw(&model.meshes[*mesh], &world);
+            }
+        }
+    }
+
+    // DEBUG
+
+    // paints shadow map i as a grey square, near is black and far is white
+    pub fn paint_shadow_map(&self, painter: &mut Painter, i: usize, x: f32, y: f32, size: f32) {
+        if let Some(map) = self.shadows.get_map(i) {
+            painter.depth_image(map, x, y, size, size);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{
+    Renderer,
+    Drawable,
+    Material,
+    Model,
+    Lights,
+    Camera,
+    CullStats,
+};
+
+use nalgebra_glm as glm;
+use std::rc::Rc;
+
+pub type NodeId = usize;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    pub translation: glm::Vec3,
+    pub rotation: glm::Quat,
+    pub scale: glm::Vec3,
+}
+
+impl Transform {
+
+    pub fn identity() -> Self {
+        Self {
+            translation: glm::vec3(0.0, 0.0, 0.0),
+            rotation: glm::quat_identity(),
+            scale: glm::vec3(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn from_translation(x: f32, y: f32, z: f32) -> Self {
+        Self { translation: glm::vec3(x, y, z), ..Self::identity() }
+    }
+
+    // translation * rotation * scale
+    pub fn matrix(&self) -> glm::Mat4 {
+        glm::translation(&self.translation)
+            * glm::quat_to_mat4(&self.rotation)
+            * glm::scaling(&self.scale)
+    }
+}
+
+pub enum NodeContent {
+    Mesh(Rc<dyn Drawable>, Material),
+    // drawn with its own materials and node hierarchy
+    Model(Rc<Model>),
+}
+
+pub struct SceneNode {
+    pub name: String,
+    // hidden nodes hide their children as well
+    pub visible: bool,
+    pub contents: Vec<NodeContent>,
+    transform: Transform,
+    world: glm::Mat4,
+    dirty: bool,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+impl SceneNode {
+    pub fn get_transform(&self) -> &Transform { &self.transform }
+    pub fn get_parent(&self) -> Option<NodeId> { self.parent }
+    pub fn get_children(&self) -> &[NodeId] { &self.children }
+}
+
+// a hierarchy of nodes, the world matrices are only recomputed for nodes
+// whose transform, or that of a parent, changed since the last update
+pub struct Scene {
+    nodes: Vec<Option<SceneNode>>,
+    roots: Vec<NodeId>,
+    renderer: Renderer,
+}
+
+impl Scene {
+
+    pub fn ne() -> Self {
+        Self {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            renderer: Renderer::ne(),
+        }
+    }
+
+    pub fn add(&mut self, name: &str, parent: Option<NodeId>) -> NodeId {
+        let node = SceneNode {
+            name: name.to_string(),
+            visible: true,
+            contents: Vec::new(),
+            transform: Transform::identity(),
+            world: glm::identity(),
+            dirty: true,
+            parent: None,
+            children: Vec::new(),
+        };
+        let id = match self.nodes.iter().position(|n| n.is_none()) {
+            Some(id) => { self.nodes[id] = Some(node); id },
+            None => { self.nodes.push(Some(node)); self.nodes.len() - 1 }
+        };
+        self.attach(id, parent);
+        id
+    }
+
+    pub fn add_mesh(&mut self, name: &str, parent: Option<NodeId>, mesh: Rc<dyn Drawable>, material: Material) -> NodeId {
+        let id = self.add(name, parent);
+        self.node_mut(id).contents.push(NodeContent::Mesh(mesh, material));
+        id
+    }
+
+    pub fn add_model(&mut self, name: &str, parent: Option<NodeId>, model: Rc<Model>) -> NodeId {
+        let id = self.add(name, parent);
+        self.node_mut(id).contents.push(NodeContent::Model(model));
+        id
+    }
+
+    // removes the node and all of its children
+    pub fn remove(&mut self, id: NodeId) {
+        self.detach(id);
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes[id].take() {
+                stack.extend(node.children);
+            }
+        }
+    }
+
+    // a node can not become a child of one of its own children
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) {
+        let mut ancestor = parent;
+        while let Some(a) = ancestor {
+            if a == id {
+                println!("Node {} can not be parented to its own child", id);
+                return
+            }
+            ancestor = self.node(a).parent;
+        }
+        self.detach(id);
+        self.attach(id, parent);
+    }
+
+    fn attach(&mut self, id: NodeId, parent: Option<NodeId>) {
+        match parent {
+            Some(p) => self.node_mut(p).children.push(id),
+            None => self.roots.push(id)
+        }
+        let node = self.node_mut(id);
+        node.parent = parent;
+        node.dirty = true;
+    }
+
+    fn detach(&mut self, id: NodeId) {
+        match self.node(id).parent {
+            Some(p) => self.node_mut(p).children.retain(|c| *c != id),
+            None => self.roots.retain(|r| *r != id)
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &SceneNode {
+        self.nodes[id].as_ref().expect("There is no SceneNode with this id")
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut SceneNode {
+        self.nodes[id].as_mut().expect("There is no SceneNode with this id")
+    }
+
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        self.nodes.iter().position(|n| n.as_ref().is_some_and(|n| n.name == name))
+    }
+
+    pub fn get_roots(&self) -> &[NodeId] { &self.roots }
+    pub fn get_renderer_mut(&mut self) -> &mut Renderer { &mut self.renderer }
+    // of the last render
+    pub fn get_stats(&self) -> CullStats { self.renderer.get_stats() }
+
+    // TRANSFORMS
+
+    pub fn set_transform(&mut self, id: NodeId, transform: Transform) {
+        let node = self.node_mut(id);
+        node.transform = transform;
+        node.dirty = true;
+    }
+
+    pub fn set_translation(&mut self, id: NodeId, x: f32, y: f32, z: f32) {
+        let mut t = *self.node(id).get_transform();
+        t.translation = glm::vec3(x, y, z);
+        self.set_transform(id, t);
+    }
+
+    pub fn set_rotation(&mut self, id: NodeId, rotation: glm::Quat) {
+        let mut t = *self.node(id).get_transform();
+        t.rotation = rotation;
+        self.set_transform(id, t);
+    }
+
+    pub fn set_scale(&mut self, id: NodeId, x: f32, y: f32, z: f32) {
+        let mut t = *self.node(id).get_transform();
+        t.scale = glm::vec3(x, y, z);
+        self.set_transform(id, t);
+    }
+
+    pub fn translate(&mut self, id: NodeId, x: f32, y: f32, z: f32) {
+        let mut t = *self.node(id).get_transform();
+        t.translation += glm::vec3(x, y, z);
+        self.set_transform(id, t);
+    }
+
+    // rotates around an axis in the parent space
+    pub fn rotate(&mut self, id: NodeId, angle: f32, axis: &glm::Vec3) {
+        let mut t = *self.node(id).get_transform();
+        t.rotation = glm::quat_angle_axis(angle, &glm::normalize(axis)) * t.rotation;
+        self.set_transform(id, t);
+    }
+
+    // recomputes the world matrices of dirty nodes and their children
+    pub fn update(&mut self) {
+        let mut stack: Vec<(NodeId, glm::Mat4, bool)> = self.roots
+            .iter()
+            .map(|r| (*r, glm::identity(), false))
+            .collect();
+        while let Some((id, parent, parent_changed)) = stack.pop() {
+            let node = self.nodes[id].as_mut().unwrap();
+            let changed = node.dirty || parent_changed;
+            if changed {
+                node.world = parent * node.transform.matrix();
+                node.dirty = false;
+            }
+            for child in node.children.iter() {
+                stack.push((*child, node.world, changed));
+            }
+        }
+    }
+
+    pub fn world_matrix(&mut self, id: NodeId) -> glm::Mat4 {
+        self.update();
+        self.node(id).world
+    }
+
+    // RENDERING
+
+    // draws every visible node with the lights that were set on the renderer before
+    pub fn render(&mut self, camera: &dyn Camera) {
+        self.update();
+        self.renderer.set_camera(&camera.view_projection(), &camera.position());
+        draw_nodes(&self.nodes, &self.roots, &mut self.renderer);
+    }
+
+    // renders the shadow maps and uploads the lights before drawing
+    pub fn render_lit(&mut self, camera: &dyn Camera, lights: &Lights) {
+        self.update();
+        self.renderer.set_camera(&camera.view_projection(), &camera.position());
+        let (nodes, roots) = (&self.nodes, &self.roots);
+        self.renderer.render_shadows(lights, |r| draw_nodes(nodes, roots, r));
+        self.renderer.set_lights(lights);
+        draw_nodes(&self.nodes, &self.roots, &mut self.renderer);
+    }
+}
+
+fn draw_nodes(nodes: &[Option<SceneNode>], roots: &[NodeId], renderer: &mut Renderer) {
+    let mut stack: Vec<NodeId> = roots.to_vec();
+    while let Some(id) = stack.pop() {
+        let node = nodes[id].as_ref().unwrap();
+        if !node.visible { continue }
+        for content in node.contents.iter() {
+            match content {
+                NodeContent::Mesh(mesh, material) => renderer.draw_mesh(&**mesh, material, &node.world),
+                NodeContent::Model(model) => renderer.draw_model(model, &node.world),
+            }
+        }
+        stack.extend_from_slice(&node.children);
+    }
+}
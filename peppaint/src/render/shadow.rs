@@ -0,0 +1,270 @@
+use crate::{
+    Framebuffer,
+    Texture,
+    ShaderProgram,
+    Light,
+    Lights,
+};
+use super::light::unit;
+
+use nalgebra_glm as glm;
+
+// sizes of the shadow uniform arrays in the material shaders
+pub const MAX_SHADOW_MAPS: usize = 8;
+pub const MAX_CASCADES: usize = 4;
+
+// shadow maps are bound after the material maps
+const SHADOW_MAP_UNIT: u32 = 4;
+// how much the cascade splits lean to logarithmic over uniform
+const CASCADE_SPLIT_LAMBDA: f32 = 0.75;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowSettings {
+    // width and height of each shadow map
+    pub resolution: usize,
+    // depth bias, the slope bias grows it on surfaces facing away from the light
+    pub bias: f32,
+    pub slope_bias: f32,
+    // PCF takes (2 * radius + 1)^2 samples, 0 means hard shadows
+    pub pcf_radius: i32,
+    // only used by directional lights, at most MAX_CASCADES
+    pub cascades: usize,
+    // how far from the camera directional shadows reach, or the range of a spot light
+    pub distance: f32,
+}
+
+impl ShadowSettings {
+    pub fn ne() -> Self {
+        Self {
+            resolution: 2048,
+            bias: 0.0005,
+            slope_bias: 0.001,
+            pcf_radius: 1,
+            cascades: 1,
+            distance: 50.0,
+        }
+    }
+
+    pub fn cascaded(cascades: usize, distance: f32) -> Self {
+        Self { cascades, distance, ..Self::ne() }
+    }
+}
+
+// the shadow maps of one light, which are consecutive for cascades
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowCaster {
+    pub light: usize,
+    pub first_map: usize,
+    pub n_maps: usize,
+}
+
+pub struct Shadows {
+    maps: Vec<Framebuffer>,
+    matrices: Vec<glm::Mat4>,
+    // far view depth of every map, only cascades have a finite one
+    splits: Vec<f32>,
+    casters: Vec<ShadowCaster>,
+    view_origin: glm::Vec3,
+    view_forward: glm::Vec3,
+    program: ShaderProgram,
+}
+
+impl Shadows {
+
+    pub fn ne() -> Self {
+        Self {
+            maps: Vec::new(),
+            matrices: Vec::new(),
+            splits: Vec::new(),
+            casters: Vec::new(),
+            view_origin: glm::vec3(0.0, 0.0, 0.0),
+            view_forward: -glm::Vec3::z(),
+            program: crate::std::shaders::depth(),
+        }
+    }
+
+    pub fn len(&self) -> usize { self.matrices.len() }
+    pub fn is_empty(&self) -> bool { self.matrices.is_empty() }
+    pub fn get_map(&self, i: usize) -> Option<&Texture> { self.maps.get(i)?.get_depth() }
+    pub fn get_matrix(&self, i: usize) -> Option<&glm::Mat4> { self.matrices.get(i) }
+    pub fn get_casters(&self) -> &[ShadowCaster] { &self.casters }
+    pub(crate) fn get_program(&mut self) -> &mut ShaderProgram { &mut self.program }
+
+    // works out the light space matrix of every shadow map for the camera, lights
+    // that do not fit in MAX_SHADOW_MAPS anymore do not cast shadows
+    pub fn prepare(&mut self, lights: &Lights, view_projection: &glm::Mat4) {
+        self.matrices.clear();
+        self.splits.clear();
+        self.casters.clear();
+        let frustum = ViewFrustum::from_vp(view_projection);
+        self.view_origin = frustum.origin;
+        self.view_forward = frustum.forward;
+
+        for (id, light) in lights.iter_with_ids() {
+            let first_map = self.matrices.len();
+            let settings = match light {
+                Light::Directional(l) => match l.shadow {
+                    Some(settings) => {
+                        let n = settings.cascades.clamp(1, MAX_CASCADES);
+                        if first_map + n > MAX_SHADOW_MAPS { continue }
+                        // without a direction there is nothing to look along
+                        let dir = match unit(&l.direction) {
+                            Some(dir) => dir,
+                            None => continue
+                        };
+                        for (near, far) in frustum.cascade_splits(n, settings.distance) {
+                            self.matrices.push(frustum.cascade_matrix(near, far, &dir, &settings));
+                            self.splits.push(far);
+                        }
+                        settings
+                    },
+                    None => continue
+                },
+                Light::Spot(l) => match l.shadow {
+                    Some(settings) => {
+                        if first_map + 1 > MAX_SHADOW_MAPS { continue }
+                        // without a direction there is nothing to look along
+                        let dir = match unit(&l.direction) {
+                            Some(dir) => dir,
+                            None => continue
+                        };
+                        let view = glm::look_at(&l.position, &(l.position + dir), &up_for(&dir));
+                        let fov = (2.0 * l.outer_angle).min(3.0);
+                        let projection = glm::perspective(1.0, fov, 0.05, settings.distance);
+                        self.matrices.push(projection * view);
+                        self.splits.push(f32::MAX);
+                        settings
+                    },
+                    None => continue
+                },
+                Light::Point(_) => continue
+            };
+
+            for i in first_map..self.matrices.len() {
+                if i >= self.maps.len() {
+                    self.maps.push(Framebuffer::depth_only(settings.resolution, settings.resolution));
+                } else if self.maps[i].get_width() != settings.resolution {
+                    self.maps[i] = Framebuffer::depth_only(settings.resolution, settings.resolution);
+                }
+            }
+            self.casters.push(ShadowCaster { light: id, first_map, n_maps: self.matrices.len() - first_map });
+        }
+    }
+
+    // binds the framebuffer of a map and clears it
+    pub(crate) fn begin_map(&self, i: usize) {
+        self.maps[i].bind();
+        unsafe { gl::Clear(gl::DEPTH_BUFFER_BIT); }
+    }
+
+    pub(crate) fn upload(&self, program: &mut ShaderProgram) {
+        program.bind();
+        program.set_vec3f("uViewOrigin", &self.view_origin);
+        program.set_vec3f("uViewForward", &self.view_forward);
+        for i in 0..self.len() {
+            program.set_int(&format!("uShadowMaps[{}]", i), (SHADOW_MAP_UNIT as usize + i) as i32);
+            program.set_mat4f(&format!("uShadowMatrices[{}]", i), self.matrices[i].as_ptr());
+            program.set_float(&format!("uCascadeSplits[{}]", i), self.splits[i]);
+            program.set_float(&format!("uShadowTexelSizes[{}]", i), 1.0 / self.maps[i].get_width() as f32);
+        }
+    }
+
+    pub(crate) fn bind_maps(&self) {
+        for i in 0..self.len() {
+            if let Some(depth) = self.maps[i].get_depth() {
+                depth.bind_to_unit(SHADOW_MAP_UNIT + i as u32);
+            }
+        }
+    }
+}
+
+fn up_for(dir: &glm::Vec3) -> glm::Vec3 {
+    if dir.y.abs() > 0.99 { glm::Vec3::x() } else { glm::Vec3::y() }
+}
+
+// the corners of the camera frustum, from the inverse view projection
+struct ViewFrustum {
+    near: [glm::Vec3; 4],
+    far: [glm::Vec3; 4],
+    origin: glm::Vec3,
+    forward: glm::Vec3,
+    // view depth of the near and far plane
+    near_depth: f32,
+    far_depth: f32,
+}
+
+impl ViewFrustum {
+
+    fn from_vp(vp: &glm::Mat4) -> Self {
+        let inv = glm::inverse(vp);
+        let corner = |x: f32, y: f32, z: f32| {
+            let p = inv * glm::vec4(x, y, z, 1.0);
+            glm::vec3(p.x, p.y, p.z) / p.w
+        };
+        let ndc = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        let mut near = [glm::vec3(0.0, 0.0, 0.0); 4];
+        let mut far = near;
+        for i in 0..4 {
+            near[i] = corner(ndc[i].0, ndc[i].1, -1.0);
+            far[i] = corner(ndc[i].0, ndc[i].1, 1.0);
+        }
+
+        // the ratio of the plane sizes gives the depths for perspective, it is 1 for ortho
+        let near_center = (near[0] + near[2]) * 0.5;
+        let far_center = (far[0] + far[2]) * 0.5;
+        let length = glm::distance(&near_center, &far_center);
+        let forward = (far_center - near_center) / length;
+        let ratio = glm::distance(&near[0], &near[2]) / glm::distance(&far[0], &far[2]);
+        let (near_depth, far_depth) = if ratio < 0.999 {
+            let f = length / (1.0 - ratio);
+            (ratio * f, f)
+        } else { (0.0, length) };
+        let origin = near_center - forward * near_depth;
+
+        Self { near, far, origin, forward, near_depth, far_depth }
+    }
+
+    fn cascade_splits(&self, n: usize, distance: f32) -> Vec<(f32, f32)> {
+        let near = self.near_depth.max(0.01);
+        let far = self.far_depth.min(distance).max(near + 0.01);
+        let mut splits = Vec::with_capacity(n);
+        let mut last = self.near_depth;
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let uniform = near + (far - near) * t;
+            let log = near * (far / near).powf(t);
+            let split = uniform + (log - uniform) * CASCADE_SPLIT_LAMBDA;
+            splits.push((last, split));
+            last = split;
+        }
+        splits
+    }
+
+    // an ortho projection around the bounding sphere of the slice, snapped to
+    // whole texels so the shadows do not shimmer when the camera moves
+    fn cascade_matrix(&self, near: f32, far: f32, dir: &glm::Vec3, settings: &ShadowSettings) -> glm::Mat4 {
+        let range = self.far_depth - self.near_depth;
+        let mut corners = Vec::with_capacity(8);
+        for depth in [near, far].iter() {
+            let t = (depth - self.near_depth) / range;
+            for i in 0..4 {
+                corners.push(self.near[i] + (self.far[i] - self.near[i]) * t);
+            }
+        }
+        let center = corners.iter().fold(glm::vec3(0.0, 0.0, 0.0), |a, b| a + b) / 8.0;
+        let radius = corners.iter().fold(0.0f32, |r, c| r.max(glm::distance(c, &center)));
+        let radius = (radius * 16.0).ceil() / 16.0;
+
+        // casters behind the slice, up to the shadow distance, are included
+        let back = settings.distance;
+        let eye = center - dir * (radius + back);
+        let view = glm::look_at(&eye, &center, &up_for(dir));
+        let mut projection = glm::ortho(-radius, radius, -radius, radius, 0.0, 2.0 * radius + back);
+
+        let half = settings.resolution as f32 / 2.0;
+        let origin = projection * view * glm::vec4(0.0, 0.0, 0.0, 1.0) * half;
+        projection[(0, 3)] += (origin.x.round() - origin.x) / half;
+        projection[(1, 3)] += (origin.y.round() - origin.y) / half;
+        projection * view
+    }
+}
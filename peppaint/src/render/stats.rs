@@ -0,0 +1,332 @@
+use crate::Painter;
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{ Write, BufWriter };
+use std::path::Path;
+use std::time::Instant;
+
+// what was sent to the gpu, counted where the draws happen
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DrawCounts {
+    pub draw_calls: usize,
+    // indexed vertices, so shared ones count once per triangle
+    pub vertices: usize,
+    // times a Painter sent its shapes
+    pub batch_flushes: usize,
+}
+
+thread_local! {
+    static DRAW_COUNTS: Cell<DrawCounts> = Cell::new(DrawCounts::default());
+}
+
+pub fn count_draw(n_vertices: usize) {
+    DRAW_COUNTS.with(|c| {
+        let mut counts = c.get();
+        counts.draw_calls += 1;
+        counts.vertices += n_vertices;
+        c.set(counts);
+    });
+}
+
+pub fn count_batch_flush() {
+    DRAW_COUNTS.with(|c| {
+        let mut counts = c.get();
+        counts.batch_flushes += 1;
+        c.set(counts);
+    });
+}
+
+// the counts since the last take
+pub fn take_draw_counts() -> DrawCounts {
+    DRAW_COUNTS.with(|c| c.replace(DrawCounts::default()))
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameSample {
+    pub frame: usize,
+    // movie time at the end of the frame
+    pub time: f64,
+    // from the start of the frame until it is shown, without vsync or sleeping
+    pub cpu_ms: f32,
+    // from the start of the last frame to the start of this one
+    pub frame_ms: f32,
+    pub draw_calls: usize,
+    pub vertices: usize,
+    pub batch_flushes: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameSummary {
+    pub frames: usize,
+    pub mean_ms: f32,
+    pub min_ms: f32,
+    pub max_ms: f32,
+    pub p95_ms: f32,
+    pub mean_draw_calls: f32,
+}
+
+pub struct FrameStats {
+    samples: VecDeque<FrameSample>,
+    // how many samples are kept, None keeps all of them
+    capacity: Option<usize>,
+    frame: usize,
+    frame_start: Instant,
+    last_frame_start: Option<Instant>,
+    pub show_overlay: bool,
+    // the frame time the graph is scaled to, in ms
+    pub budget_ms: f32,
+    // made when the overlay is first shown
+    painter: Option<Painter>,
+}
+
+impl FrameStats {
+
+    pub fn ne() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: Some(600),
+            frame: 0,
+            frame_start: Instant::now(),
+            last_frame_start: None,
+            show_overlay: false,
+            budget_ms: 1000.0 / 60.0,
+            painter: None,
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        let now = Instant::now();
+        self.last_frame_start = Some(self.frame_start);
+        self.frame_start = now;
+    }
+
+    // takes the draw counts, so everything drawn after this goes into the next frame
+    pub fn record(&mut self, time: f64) -> FrameSample {
+        let counts = take_draw_counts();
+        let frame_ms = self.last_frame_start
+            .map_or(0.0, |last| (self.frame_start - last).as_secs_f32() * 1000.0);
+        let sample = FrameSample {
+            frame: self.frame,
+            time,
+            cpu_ms: self.frame_start.elapsed().as_secs_f32() * 1000.0,
+            frame_ms,
+            draw_calls: counts.draw_calls,
+            vertices: counts.vertices,
+            batch_flushes: counts.batch_flushes,
+        };
+        self.frame += 1;
+        self.samples.push_back(sample);
+        if let Some(capacity) = self.capacity {
+            while self.samples.len() > capacity {
+                self.samples.pop_front();
+            }
+        }
+        sample
+    }
+
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        if let Some(capacity) = capacity {
+            while self.samples.len() > capacity {
+                self.samples.pop_front();
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn toggle_overlay(&mut self) {
+        self.show_overlay = !self.show_overlay;
+    }
+
+    pub fn get_samples(&self) -> &VecDeque<FrameSample> {
+        &self.samples
+    }
+
+    pub fn last(&self) -> Option<FrameSample> {
+        self.samples.back().copied()
+    }
+
+    pub fn summary(&self) -> FrameSummary {
+        let n = self.samples.len();
+        if n == 0 { return FrameSummary::default() }
+        let mut times: Vec<f32> = self.samples.iter().map(|s| s.cpu_ms).collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        FrameSummary {
+            frames: n,
+            mean_ms: times.iter().sum::<f32>() / n as f32,
+            min_ms: times[0],
+            max_ms: times[n - 1],
+            p95_ms: times[((n - 1) as f32 * 0.95) as usize],
+            mean_draw_calls: self.samples.iter().map(|s| s.draw_calls).sum::<usize>() as f32 / n as f32,
+        }
+    }
+
+    // EXPORT
+
+    pub fn to_csv(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Could not create .csv file: {}", e))?;
+        self.write_csv(&mut BufWriter::new(file))
+            .map_err(|e| format!("Could not write .csv file: {}", e))
+    }
+
+    pub fn to_json(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Could not create .json file: {}", e))?;
+        self.write_json(&mut BufWriter::new(file))
+            .map_err(|e| format!("Could not write .json file: {}", e))
+    }
+
+    // json for a .json path, csv otherwise
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => self.to_json(path),
+            _ => self.to_csv(path),
+        }
+    }
+
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "frame,time,cpu_ms,frame_ms,draw_calls,vertices,batch_flushes")?;
+        for s in &self.samples {
+            writeln!(w, "{},{:.6},{:.4},{:.4},{},{},{}",
+                s.frame, s.time, s.cpu_ms, s.frame_ms, s.draw_calls, s.vertices, s.batch_flushes)?;
+        }
+        w.flush()
+    }
+
+    pub fn write_json<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        let summary = self.summary();
+        writeln!(w, "{{")?;
+        writeln!(w, "  \"summary\": {{ \"frames\": {}, \"mean_ms\": {:.4}, \"min_ms\": {:.4}, \"max_ms\": {:.4}, \"p95_ms\": {:.4}, \"mean_draw_calls\": {:.2} }},",
+            summary.frames, summary.mean_ms, summary.min_ms, summary.max_ms, summary.p95_ms, summary.mean_draw_calls)?;
+        writeln!(w, "  \"frames\": [")?;
+        let n = self.samples.len();
+        for (i, s) in self.samples.iter().enumerate() {
+            let comma = if i + 1 < n { "," } else { "" };
+            writeln!(w, "    {{ \"frame\": {}, \"time\": {:.6}, \"cpu_ms\": {:.4}, \"frame_ms\": {:.4}, \"draw_calls\": {}, \"vertices\": {}, \"batch_flushes\": {} }}{}",
+                s.frame, s.time, s.cpu_ms, s.frame_ms, s.draw_calls, s.vertices, s.batch_flushes, comma)?;
+        }
+        writeln!(w, "  ]")?;
+        writeln!(w, "}}")?;
+        w.flush()
+    }
+
+    // OVERLAY
+
+    // graphs of the last frames in the top left corner: the frame times against the
+    // budget line, and below them the draw calls against the most in view
+    pub fn paint_overlay(&mut self) {
+        const BARS: usize = 120;
+        const BAR_WIDTH: f32 = 2.5;
+        const HEIGHT: f32 = 60.0;
+        const MARGIN: f32 = 10.0;
+
+        if self.painter.is_none() {
+            let mut painter = Painter::ne();
+            painter.fit_screen();
+            self.painter = Some(painter);
+        }
+        let p = self.painter.as_mut().unwrap();
+        let width = BARS as f32 * BAR_WIDTH;
+        let first = self.samples.len().saturating_sub(BARS);
+        let shown: Vec<&FrameSample> = self.samples.iter().skip(first).collect();
+
+        p.color_alpha(0.0, 0.0, 0.0, 0.6);
+        p.rect(MARGIN, MARGIN, width, 2.0 * HEIGHT + MARGIN);
+
+        // frame times, twice the budget is the top of the graph
+        let bottom = MARGIN + HEIGHT;
+        let scale = HEIGHT / (2.0 * self.budget_ms);
+        for (i, s) in shown.iter().enumerate() {
+            let x = MARGIN + i as f32 * BAR_WIDTH;
+            let frame_h = (s.frame_ms * scale).min(HEIGHT);
+            p.color_alpha(0.5, 0.5, 0.5, 0.5);
+            p.rect(x, bottom - frame_h, BAR_WIDTH, frame_h);
+            let cpu_h = (s.cpu_ms * scale).min(HEIGHT);
+            let load = s.cpu_ms / self.budget_ms;
+            if load < 0.5 {
+                p.color(0.2, 0.9, 0.3);
+            } else if load < 1.0 {
+                p.color(0.95, 0.8, 0.2);
+            } else {
+                p.color(0.95, 0.25, 0.2);
+            }
+            p.rect(x, bottom - cpu_h, BAR_WIDTH, cpu_h);
+        }
+        p.color_alpha(1.0, 1.0, 1.0, 0.8);
+        p.rect(MARGIN, bottom - HEIGHT / 2.0, width, 1.0);
+
+        // draw calls
+        let bottom = bottom + MARGIN + HEIGHT;
+        let most = shown.iter().map(|s| s.draw_calls).max().unwrap_or(0).max(1);
+        p.color(0.3, 0.6, 1.0);
+        for (i, s) in shown.iter().enumerate() {
+            let h = s.draw_calls as f32 / most as f32 * HEIGHT;
+            p.rect(MARGIN + i as f32 * BAR_WIDTH, bottom - h, BAR_WIDTH, h);
+        }
+
+        p.paint();
+        // the overlay itself is not part of the next frame
+        take_draw_counts();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // cpu times of 20 down to 1 ms, out of order on purpose
+    fn stats() -> FrameStats {
+        let mut stats = FrameStats::ne();
+        for i in 0..20 {
+            stats.samples.push_back(FrameSample {
+                frame: i,
+                time: i as f64 / 60.0,
+                cpu_ms: (20 - i) as f32,
+                frame_ms: 16.0,
+                draw_calls: 2 * i,
+                ..Default::default()
+            });
+        }
+        stats
+    }
+
+    #[test]
+    fn summary() {
+        let summary = stats().summary();
+        assert_eq!(summary.frames, 20);
+        assert_eq!(summary.min_ms, 1.0);
+        assert_eq!(summary.max_ms, 20.0);
+        assert_eq!(summary.p95_ms, 19.0);
+        assert_eq!(summary.mean_ms, 10.5);
+        assert_eq!(summary.mean_draw_calls, 19.0);
+        assert_eq!(FrameStats::ne().summary().frames, 0);
+    }
+
+    #[test]
+    fn csv() {
+        let mut out = vec![];
+        stats().write_csv(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "frame,time,cpu_ms,frame_ms,draw_calls,vertices,batch_flushes");
+        assert_eq!(lines.len(), 21);
+        assert!(lines[1].starts_with("0,0.000000,20.0000,16.0000,0,"));
+        assert!(lines.iter().all(|l| l.split(',').count() == 7));
+    }
+
+    #[test]
+    fn json() {
+        let mut out = vec![];
+        stats().write_json(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("\"frames\": 20, \"mean_ms\": 10.5000"));
+        assert!(text.contains("\"p95_ms\": 19.0000"));
+        assert_eq!(text.matches("{ \"frame\":").count(), 20);
+        // no comma after the last frame
+        assert!(text.contains("}\n  ]"));
+    }
+}
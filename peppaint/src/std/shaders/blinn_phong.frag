@@ -0,0 +1,22 @@
+
+void main() {
+    vec4 base = baseColor();
+    vec3 n = surfaceNormal();
+    vec3 v = normalize(uCameraPosition - oWorldPosition);
+
+    vec3 specularColor = uMaterial.specular;
+    if (uMaterial.hasSpecularMap) {
+        specularColor *= texture(uMaterial.specularMap, oTexCoord).rgb;
+    }
+
+    vec3 color = uAmbientLight * uMaterial.ambient * base.rgb + uMaterial.emissive;
+    for (int i = 0; i < min(uNLights, MAX_LIGHTS); i++) {
+        vec3 l;
+        vec3 radiance = incomingLight(i, n, l);
+        vec3 h = normalize(l + v);
+        float diff = max(dot(n, l), 0.0);
+        float spec = diff > 0.0 ? pow(max(dot(n, h), 0.0), max(uMaterial.shininess, 1.0)) : 0.0;
+        color += radiance * (diff * base.rgb + spec * specularColor);
+    }
+    oColor = vec4(color, base.a);
+}
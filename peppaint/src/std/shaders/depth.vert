@@ -0,0 +1,10 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPosition;
+
+uniform mat4 uViewProjection = mat4(1.0);
+uniform mat4 uModel = mat4(1.0);
+
+void main() {
+    gl_Position = uViewProjection * uModel * vec4(aPosition, 1.0);
+}
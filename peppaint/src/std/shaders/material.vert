@@ -0,0 +1,27 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPosition;
+layout (location = 1) in vec3 aNormal;
+layout (location = 2) in vec2 aTexCoord;
+// (0, 0, 0, 1) when the mesh has no tangents, e.g. PosNormTex
+layout (location = 3) in vec4 aTangent;
+
+uniform mat4 uViewProjection = mat4(1.0);
+uniform mat4 uModel = mat4(1.0);
+
+out vec3 oWorldPosition;
+out vec3 oNormal;
+out vec2 oTexCoord;
+out vec4 oTangent;
+
+void main() {
+    vec4 world = uModel * vec4(aPosition, 1.0);
+    mat3 normalMatrix = transpose(inverse(mat3(uModel)));
+
+    oWorldPosition = world.xyz;
+    oNormal = normalMatrix * aNormal;
+    oTexCoord = aTexCoord;
+    oTangent = vec4(mat3(uModel) * aTangent.xyz, aTangent.w);
+
+    gl_Position = uViewProjection * world;
+}
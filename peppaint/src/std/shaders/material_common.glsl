@@ -0,0 +1,178 @@
+#version 330 core
+
+struct Material {
+    vec3 ambient;
+    vec3 diffuse;
+    vec3 specular;
+    float shininess;
+    float dissolve;
+    vec3 emissive;
+    float metallic;
+    float roughness;
+
+    sampler2D diffuseMap;
+    sampler2D specularMap;
+    sampler2D normalMap;
+    sampler2D metallicRoughnessMap;
+    bool hasDiffuseMap;
+    bool hasSpecularMap;
+    bool hasNormalMap;
+    bool hasMetallicRoughnessMap;
+};
+
+uniform Material uMaterial;
+uniform vec3 uCameraPosition = vec3(0.0, 0.0, 0.0);
+
+// must match MAX_LIGHTS in render/light.rs
+#define MAX_LIGHTS 16
+#define DIRECTIONAL 0
+#define POINT 1
+#define SPOT 2
+
+struct Light {
+    int type;
+    vec3 position;
+    vec3 direction;
+    // already multiplied by the intensity
+    vec3 color;
+    // constant, linear, quadratic
+    vec3 attenuation;
+    float innerCos;
+    float outerCos;
+    // index of the first shadow map, -1 when the light casts no shadows
+    int shadow;
+    int cascades;
+    float bias;
+    float slopeBias;
+    int pcf;
+};
+
+uniform Light uLights[MAX_LIGHTS];
+uniform int uNLights = 0;
+uniform vec3 uAmbientLight = vec3(0.1, 0.1, 0.1);
+
+// must match MAX_SHADOW_MAPS in render/shadow.rs
+#define MAX_SHADOW_MAPS 8
+
+uniform sampler2D uShadowMaps[MAX_SHADOW_MAPS];
+uniform mat4 uShadowMatrices[MAX_SHADOW_MAPS];
+// the view depth up to which a cascade is used
+uniform float uCascadeSplits[MAX_SHADOW_MAPS];
+uniform float uShadowTexelSizes[MAX_SHADOW_MAPS];
+uniform vec3 uViewOrigin;
+uniform vec3 uViewForward;
+
+in vec3 oWorldPosition;
+in vec3 oNormal;
+in vec2 oTexCoord;
+in vec4 oTangent;
+
+out vec4 oColor;
+
+vec4 baseColor() {
+    vec4 color = vec4(uMaterial.diffuse, uMaterial.dissolve);
+    if (uMaterial.hasDiffuseMap) {
+        color *= texture(uMaterial.diffuseMap, oTexCoord);
+    }
+    return color;
+}
+
+// the normal map is applied in tangent space, without vertex tangents
+// the frame is reconstructed from the screen space derivatives
+vec3 surfaceNormal() {
+    vec3 n = normalize(oNormal);
+    if (!uMaterial.hasNormalMap) {
+        return n;
+    }
+    vec3 t, b;
+    if (dot(oTangent.xyz, oTangent.xyz) > 0.0001) {
+        t = normalize(oTangent.xyz - n * dot(n, oTangent.xyz));
+        b = cross(n, t) * oTangent.w;
+    } else {
+        vec3 dp1 = dFdx(oWorldPosition);
+        vec3 dp2 = dFdy(oWorldPosition);
+        vec2 duv1 = dFdx(oTexCoord);
+        vec2 duv2 = dFdy(oTexCoord);
+        vec3 dp2perp = cross(dp2, n);
+        vec3 dp1perp = cross(n, dp1);
+        t = dp2perp * duv1.x + dp1perp * duv2.x;
+        b = dp2perp * duv1.y + dp1perp * duv2.y;
+        float invmax = inversesqrt(max(dot(t, t), dot(b, b)));
+        t *= invmax;
+        b *= invmax;
+    }
+    vec3 mapped = texture(uMaterial.normalMap, oTexCoord).xyz * 2.0 - 1.0;
+    return normalize(mat3(t, b, n) * mapped);
+}
+
+// sampler arrays can only be indexed with constants in glsl 330
+float shadowMapDepth(int map, vec2 uv) {
+    if (map == 0) return texture(uShadowMaps[0], uv).r;
+    if (map == 1) return texture(uShadowMaps[1], uv).r;
+    if (map == 2) return texture(uShadowMaps[2], uv).r;
+    if (map == 3) return texture(uShadowMaps[3], uv).r;
+    if (map == 4) return texture(uShadowMaps[4], uv).r;
+    if (map == 5) return texture(uShadowMaps[5], uv).r;
+    if (map == 6) return texture(uShadowMaps[6], uv).r;
+    return texture(uShadowMaps[7], uv).r;
+}
+
+// 1 when lit, 0 when fully in shadow
+float shadowFactor(Light light, vec3 n, vec3 l) {
+    if (light.shadow < 0) {
+        return 1.0;
+    }
+
+    int map = light.shadow;
+    if (light.cascades > 1) {
+        float depth = dot(oWorldPosition - uViewOrigin, uViewForward);
+        int last = light.shadow + light.cascades - 1;
+        if (depth > uCascadeSplits[last]) {
+            return 1.0;
+        }
+        while (map < last && depth > uCascadeSplits[map]) {
+            map++;
+        }
+    }
+
+    vec4 p = uShadowMatrices[map] * vec4(oWorldPosition, 1.0);
+    vec3 coords = p.xyz / p.w * 0.5 + 0.5;
+    if (coords.z > 1.0) {
+        return 1.0;
+    }
+
+    float cosTheta = clamp(dot(n, l), 0.05, 1.0);
+    float bias = light.bias + light.slopeBias * sqrt(1.0 - cosTheta * cosTheta) / cosTheta;
+    float texel = uShadowTexelSizes[map];
+
+    float lit = 0.0;
+    for (int x = -light.pcf; x <= light.pcf; x++) {
+        for (int y = -light.pcf; y <= light.pcf; y++) {
+            float closest = shadowMapDepth(map, coords.xy + vec2(x, y) * texel);
+            lit += coords.z - bias > closest ? 0.0 : 1.0;
+        }
+    }
+    float nSamples = float((2 * light.pcf + 1) * (2 * light.pcf + 1));
+    return lit / nSamples;
+}
+
+// direction towards the light and the radiance arriving at the fragment
+vec3 incomingLight(int i, vec3 n, out vec3 l) {
+    Light light = uLights[i];
+    if (light.type == DIRECTIONAL) {
+        l = normalize(-light.direction);
+        return light.color * shadowFactor(light, n, l);
+    }
+
+    vec3 toLight = light.position - oWorldPosition;
+    float d = length(toLight);
+    l = toLight / d;
+    vec3 a = light.attenuation;
+    float falloff = 1.0 / max(a.x + a.y * d + a.z * d * d, 0.0001);
+
+    if (light.type == SPOT) {
+        float theta = dot(-l, normalize(light.direction));
+        falloff *= smoothstep(light.outerCos, light.innerCos, theta);
+    }
+    return light.color * falloff * shadowFactor(light, n, l);
+}
@@ -5,4 +5,44 @@ pub fn pos_color() -> ShaderProgram {
         include_str!("./pos_color.frag"),
         include_str!("./pos_color.vert"),
     ).expect("Could not load PosColor Shader ShaderProgram")
-}
\ No newline at end of file
+}
+
+// shaders for the Material shading models, they all share the same vertex shader
+// that takes a PosNormTex or PosNormTexTan vertex
+
+pub fn unlit() -> ShaderProgram {
+    ShaderProgram::from_frag_and_vert_src(
+        concat!(include_str!("./material_common.glsl"), include_str!("./unlit.frag")),
+        include_str!("./material.vert"),
+    ).expect("Could not load Unlit ShaderProgram")
+}
+
+pub fn blinn_phong() -> ShaderProgram {
+    ShaderProgram::from_frag_and_vert_src(
+        concat!(include_str!("./material_common.glsl"), include_str!("./blinn_phong.frag")),
+        include_str!("./material.vert"),
+    ).expect("Could not load Blinn-Phong ShaderProgram")
+}
+
+pub fn pbr() -> ShaderProgram {
+    ShaderProgram::from_frag_and_vert_src(
+        concat!(include_str!("./material_common.glsl"), include_str!("./pbr.frag")),
+        include_str!("./material.vert"),
+    ).expect("Could not load PBR ShaderProgram")
+}
+
+// depth only, for shadow maps
+pub fn depth() -> ShaderProgram {
+    ShaderProgram::from_frag_and_vert_src(
+        include_str!("./depth.frag"),
+        include_str!("./depth.vert"),
+    ).expect("Could not load Depth ShaderProgram")
+}
+
+// blends two offscreen frames, drawn with 3 vertices and no buffers
+pub fn transition() -> ShaderProgram {
+    ShaderProgram::from_frag_and_vert_src(
+        include_str!("./transition.frag"),
+        include_str!("./transition.vert"),
+    ).expect("Could not load Transition ShaderProgram")
+}
@@ -0,0 +1,16 @@
+#version 330 core
+
+in vec4 oColor;
+in vec2 oTexCoord;
+
+uniform sampler2D uTexture;
+uniform bool uDepth;
+
+out vec4 color;
+
+void main()
+{
+    vec4 texel = texture(uTexture, oTexCoord);
+    if (uDepth) { texel = vec4(texel.rrr, 1.0); }
+    color = oColor * texel;
+}
@@ -0,0 +1,17 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPosition;
+layout (location = 1) in vec4 aColor;
+layout (location = 2) in vec2 aTexCoord;
+
+uniform mat4 uViewProjection;
+
+out vec4 oColor;
+out vec2 oTexCoord;
+
+void main()
+{
+    gl_Position = uViewProjection * vec4(aPosition.xy, 1.0, 1.0);
+    oColor = aColor;
+    oTexCoord = aTexCoord;
+}
@@ -0,0 +1,59 @@
+
+const float PI = 3.14159265359;
+
+float distributionGGX(float NdotH, float roughness) {
+    float a = roughness * roughness;
+    float a2 = a * a;
+    float d = NdotH * NdotH * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+float geometrySchlickGGX(float NdotX, float roughness) {
+    float r = roughness + 1.0;
+    float k = r * r / 8.0;
+    return NdotX / (NdotX * (1.0 - k) + k);
+}
+
+vec3 fresnelSchlick(float cosTheta, vec3 f0) {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cosTheta, 0.0, 1.0), 5.0);
+}
+
+// cook-torrance specular with a lambertian diffuse, for one light
+vec3 brdf(vec3 n, vec3 v, vec3 l, vec3 albedo, float metallic, float roughness) {
+    vec3 h = normalize(v + l);
+    float NdotL = max(dot(n, l), 0.0);
+    float NdotV = max(dot(n, v), 0.0001);
+    float NdotH = max(dot(n, h), 0.0);
+
+    vec3 f0 = mix(vec3(0.04), albedo, metallic);
+    vec3 f = fresnelSchlick(max(dot(h, v), 0.0), f0);
+    float d = distributionGGX(NdotH, roughness);
+    float g = geometrySchlickGGX(NdotV, roughness) * geometrySchlickGGX(NdotL, roughness);
+
+    vec3 specular = d * g * f / (4.0 * NdotV * NdotL + 0.0001);
+    vec3 kd = (1.0 - f) * (1.0 - metallic);
+    return (kd * albedo / PI + specular) * NdotL;
+}
+
+void main() {
+    vec4 base = baseColor();
+    float metallic = uMaterial.metallic;
+    float roughness = uMaterial.roughness;
+    if (uMaterial.hasMetallicRoughnessMap) {
+        vec4 mr = texture(uMaterial.metallicRoughnessMap, oTexCoord);
+        metallic *= mr.b;
+        roughness *= mr.g;
+    }
+    roughness = clamp(roughness, 0.04, 1.0);
+
+    vec3 n = surfaceNormal();
+    vec3 v = normalize(uCameraPosition - oWorldPosition);
+
+    vec3 color = uAmbientLight * base.rgb + uMaterial.emissive;
+    for (int i = 0; i < min(uNLights, MAX_LIGHTS); i++) {
+        vec3 l;
+        vec3 radiance = incomingLight(i, n, l);
+        color += radiance * brdf(n, v, l, base.rgb, metallic, roughness);
+    }
+    oColor = vec4(color, base.a);
+}
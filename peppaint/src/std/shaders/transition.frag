@@ -0,0 +1,25 @@
+#version 330 core
+
+in vec2 oTexCoord;
+
+uniform sampler2D uFrom;
+uniform sampler2D uTo;
+uniform float uProgress;
+// 0 is a cross-fade, 1 a wipe from left to right
+uniform int uKind;
+// the width of the wipe edge
+uniform float uSoftness = 0.05;
+
+out vec4 color;
+
+void main()
+{
+    vec4 from = texture(uFrom, oTexCoord);
+    vec4 to = texture(uTo, oTexCoord);
+    float t = uProgress;
+    if (uKind == 1) {
+        float edge = uProgress * (1.0 + uSoftness);
+        t = 1.0 - smoothstep(edge - uSoftness, edge, oTexCoord.x);
+    }
+    color = vec4(mix(from.rgb, to.rgb, t), 1.0);
+}
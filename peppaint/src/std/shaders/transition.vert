@@ -0,0 +1,10 @@
+#version 330 core
+
+// a triangle covering the screen, made from the vertex id so no buffers are needed
+out vec2 oTexCoord;
+
+void main() {
+    vec2 p = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+    oTexCoord = p;
+    gl_Position = vec4(p * 2.0 - 1.0, 0.0, 1.0);
+}
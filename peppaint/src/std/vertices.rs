@@ -1,12 +1,14 @@
 use crate::{
     Vec3,
     Vec2,
+    Vec4,
     AttributeLayout,
     Vertex,
     Float,
     Color,
     VertexLayout,
-    Type
+    Type,
+    ExportVertex
 };
 
 #[repr(C, packed)]
@@ -44,6 +46,24 @@ pub struct PosColorTex {
     pub texcoords: Vec2
 }
 
+#[repr(C, packed)]
+#[derive(Clone, Debug, Copy)]
+pub struct PosNormTex {
+    pub pos: Vec3,
+    pub normal: Vec3,
+    pub texcoords: Vec2
+}
+
+// tangent.w is the handedness of the bitangent, cross(normal, tangent) * w
+#[repr(C, packed)]
+#[derive(Clone, Debug, Copy)]
+pub struct PosNormTexTan {
+    pub pos: Vec3,
+    pub normal: Vec3,
+    pub texcoords: Vec2,
+    pub tangent: Vec4
+}
+
 impl Vertex for PaintingVertex {
     fn get_layout() -> VertexLayout {
         let stride = 28;
@@ -51,19 +71,25 @@ impl Vertex for PaintingVertex {
             location: 0,
             n_elements: 2, 
             byte_offset: 0,
-            type_: Type::Float
+            type_: Type::Float,
+            normalized: false,
+            integer: false
         };
         let al2 = AttributeLayout {
             location: 1,
             n_elements: 4,
             byte_offset: 8,
-            type_: Type::Float
+            type_: Type::Float,
+            normalized: false,
+            integer: false
         };
         let al3 = AttributeLayout {
             location: 2,
             n_elements: 1,
             byte_offset: 24,
-            type_: Type::Float
+            type_: Type::Float,
+            normalized: false,
+            integer: false
         };
         let als = vec![al1, al2, al3];
         VertexLayout {
@@ -80,19 +106,25 @@ impl Vertex for DepricPaintingVertex {
             location: 0,
             n_elements: 3, 
             byte_offset: 0,
-            type_: Type::Float
+            type_: Type::Float,
+            normalized: false,
+            integer: false
         };
         let al2 = AttributeLayout {
             location: 1,
             n_elements: 4,
             byte_offset: 12,
-            type_: Type::Float
+            type_: Type::Float,
+            normalized: false,
+            integer: false
         };
         let al3 = AttributeLayout {
             location: 2,
             n_elements: 1,
             byte_offset: 28,
-            type_: Type::Float
+            type_: Type::Float,
+            normalized: false,
+            integer: false
         };
         let als = vec![al1, al2, al3];
         VertexLayout {
@@ -110,7 +142,9 @@ impl Vertex for Pos {
             location: 0,
             n_elements: 3,
             type_: Type::Float,
-            byte_offset: 0
+            byte_offset: 0,
+            normalized: false,
+            integer: false
         };
 
         let attrib_layouts = vec![al1];
@@ -129,14 +163,18 @@ impl Vertex for PosColor {
             location: 0,
             n_elements: 3,
             type_: Type::Float,
-            byte_offset: 0
+            byte_offset: 0,
+            normalized: false,
+            integer: false
         };
 
         let al2 = AttributeLayout {
             location: 1,
             n_elements: 4,
             type_: Type::Float,
-            byte_offset: 12
+            byte_offset: 12,
+            normalized: false,
+            integer: false
         };
 
         let attrib_layouts = vec![al1, al2];
@@ -145,4 +183,164 @@ impl Vertex for PosColor {
             attrib_layouts
         }
     }
+}
+
+impl Vertex for PosColorTex {
+    fn get_layout() -> VertexLayout {
+        let stride = 36;
+
+        let al1 = AttributeLayout {
+            location: 0,
+            n_elements: 3,
+            type_: Type::Float,
+            byte_offset: 0,
+            normalized: false,
+            integer: false
+        };
+
+        let al2 = AttributeLayout {
+            location: 1,
+            n_elements: 4,
+            type_: Type::Float,
+            byte_offset: 12,
+            normalized: false,
+            integer: false
+        };
+
+        let al3 = AttributeLayout {
+            location: 2,
+            n_elements: 2,
+            type_: Type::Float,
+            byte_offset: 28,
+            normalized: false,
+            integer: false
+        };
+
+        let attrib_layouts = vec![al1, al2, al3];
+        VertexLayout {
+            stride,
+            attrib_layouts
+        }
+    }
+}
+
+impl Vertex for PosNormTex {
+    fn get_layout() -> VertexLayout {
+        let stride = 32;
+
+        let al1 = AttributeLayout {
+            location: 0,
+            n_elements: 3,
+            type_: Type::Float,
+            byte_offset: 0,
+            normalized: false,
+            integer: false
+        };
+
+        let al2 = AttributeLayout {
+            location: 1,
+            n_elements: 3,
+            type_: Type::Float,
+            byte_offset: 12,
+            normalized: false,
+            integer: false
+        };
+
+        let al3 = AttributeLayout {
+            location: 2,
+            n_elements: 2,
+            type_: Type::Float,
+            byte_offset: 24,
+            normalized: false,
+            integer: false
+        };
+
+        let attrib_layouts = vec![al1, al2, al3];
+        VertexLayout {
+            stride,
+            attrib_layouts
+        }
+    }
+}
+
+impl Vertex for PosNormTexTan {
+    fn get_layout() -> VertexLayout {
+        let stride = 48;
+
+        let al1 = AttributeLayout {
+            location: 0,
+            n_elements: 3,
+            type_: Type::Float,
+            byte_offset: 0,
+            normalized: false,
+            integer: false
+        };
+
+        let al2 = AttributeLayout {
+            location: 1,
+            n_elements: 3,
+            type_: Type::Float,
+            byte_offset: 12,
+            normalized: false,
+            integer: false
+        };
+
+        let al3 = AttributeLayout {
+            location: 2,
+            n_elements: 2,
+            type_: Type::Float,
+            byte_offset: 24,
+            normalized: false,
+            integer: false
+        };
+
+        let al4 = AttributeLayout {
+            location: 3,
+            n_elements: 4,
+            type_: Type::Float,
+            byte_offset: 32,
+            normalized: false,
+            integer: false
+        };
+
+        let attrib_layouts = vec![al1, al2, al3, al4];
+        VertexLayout {
+            stride,
+            attrib_layouts
+        }
+    }
+}
+
+// EXPORTING
+
+impl ExportVertex for Pos {
+    fn position(&self) -> [f32; 3] { [self.0, self.1, self.2] }
+}
+
+impl ExportVertex for PosColor {
+    fn position(&self) -> [f32; 3] { let p = self.pos; [p.0, p.1, p.2] }
+    fn color(&self) -> Option<[f32; 4]> { let c = self.color; Some([c.0, c.1, c.2, c.3]) }
+}
+
+impl ExportVertex for PosColorTex {
+    fn position(&self) -> [f32; 3] { let p = self.pos; [p.0, p.1, p.2] }
+    fn color(&self) -> Option<[f32; 4]> { let c = self.color; Some([c.0, c.1, c.2, c.3]) }
+    fn texcoords(&self) -> Option<[f32; 2]> { let t = self.texcoords; Some([t.0, t.1]) }
+}
+
+impl ExportVertex for PosNormTex {
+    fn position(&self) -> [f32; 3] { let p = self.pos; [p.0, p.1, p.2] }
+    fn normal(&self) -> Option<[f32; 3]> { let n = self.normal; Some([n.0, n.1, n.2]) }
+    fn texcoords(&self) -> Option<[f32; 2]> { let t = self.texcoords; Some([t.0, t.1]) }
+}
+
+impl ExportVertex for PosNormTexTan {
+    fn position(&self) -> [f32; 3] { let p = self.pos; [p.0, p.1, p.2] }
+    fn normal(&self) -> Option<[f32; 3]> { let n = self.normal; Some([n.0, n.1, n.2]) }
+    fn texcoords(&self) -> Option<[f32; 2]> { let t = self.texcoords; Some([t.0, t.1]) }
+}
+
+impl ExportVertex for PaintingVertex {
+    fn position(&self) -> [f32; 3] { let p = self.pos; [p.0, p.1, 0.0] }
+    fn color(&self) -> Option<[f32; 4]> { let c = self.color; Some([c.0, c.1, c.2, c.3]) }
 }
\ No newline at end of file
@@ -1,12 +1,19 @@
 use gl::types::*;
 use crate::constants::EPSILON;
+use nalgebra_glm as glm;
 
 pub enum Type {
     Float,
+    HalfFloat,
     Int,
     Uint,
+    Short,
+    Ushort,
     Byte,
-    Ubyte
+    Ubyte,
+    // four components packed in a single 32 bit word, w in the top 2 bits
+    Int2_10_10_10Rev,
+    Uint2_10_10_10Rev
 }
 
 impl Type {
@@ -14,12 +21,28 @@ impl Type {
         use Type::*;
         match self {
             Float => gl::FLOAT,
+            HalfFloat => gl::HALF_FLOAT,
             Int => gl::INT,
             Uint => gl::UNSIGNED_INT,
+            Short => gl::SHORT,
+            Ushort => gl::UNSIGNED_SHORT,
             Byte => gl::BYTE,
-            Ubyte => gl::UNSIGNED_BYTE
+            Ubyte => gl::UNSIGNED_BYTE,
+            Int2_10_10_10Rev => gl::INT_2_10_10_10_REV,
+            Uint2_10_10_10Rev => gl::UNSIGNED_INT_2_10_10_10_REV
         }
     }
+
+    // only these can be read as ints/uints in the shader (glVertexAttribIPointer)
+    pub fn is_integer(&self) -> bool {
+        use Type::*;
+        matches!(self, Int | Uint | Short | Ushort | Byte | Ubyte)
+    }
+
+    pub fn is_packed(&self) -> bool {
+        use Type::*;
+        matches!(self, Int2_10_10_10Rev | Uint2_10_10_10Rev)
+    }
 }
 
 pub type Float = GLfloat;
@@ -93,6 +116,9 @@ impl Vec3 {
         (self.0*self.0 + self.1*self.1 + self.2*self.2).sqrt()
     }
 
+    pub fn to_glm(&self) -> glm::Vec3 { glm::vec3(self.0, self.1, self.2) }
+    pub fn from_glm(v: &glm::Vec3) -> Self { Vec3(v.x, v.y, v.z) }
+
     pub fn add(a: &Self, b: &Self) -> Self {
         Vec3(
             a.0 + b.0,
@@ -227,6 +253,11 @@ impl Mat4 {
         )
     }
 
+    pub fn from_glm(m: &glm::Mat4) -> Self {
+        let row = |i: usize| Vec4(m[(i, 0)], m[(i, 1)], m[(i, 2)], m[(i, 3)]);
+        Mat4(row(0), row(1), row(2), row(3))
+    }
+
     pub fn uninit() -> Self {
         use std::mem::MaybeUninit;
         unsafe { MaybeUninit::uninit().assume_init() }
@@ -323,6 +354,24 @@ impl Mat4 {
         rot
     }
 
+    // the inverse of the rotation taking E3 to heading and E2 to up, the up
+    // vector only needs to be roughly perpendicular to the heading
+    pub fn inv_rotation_from_frame(heading: &Vec3, up: &Vec3) -> Self {
+        let f = heading.to_glm().normalize();
+        let mut right = glm::cross(&up.to_glm(), &f);
+        if glm::length(&right) < 1e-6 {
+            right = glm::cross(&crate::perpendicular(&f), &f);
+        }
+        let r = right.normalize();
+        let u = glm::cross(&f, &r);
+        Self(
+            Vec4(r.x, r.y, r.z, 0.0),
+            Vec4(u.x, u.y, u.z, 0.0),
+            Vec4(f.x, f.y, f.z, 0.0),
+            Vec4(0.0, 0.0, 0.0, 1.0)
+        )
+    }
+
     pub fn perspective(
         near: Float, 
         far: Float, 
@@ -449,6 +498,17 @@ impl Mat4 {
 
     // IMMUTUALS
 
+    // the rows become the rows of the glm matrix, which is column major
+    pub fn to_glm(&self) -> glm::Mat4 {
+        let (a, b, c, d) = (self.0, self.1, self.2, self.3);
+        glm::mat4(
+            a.0, a.1, a.2, a.3,
+            b.0, b.1, b.2, b.3,
+            c.0, c.1, c.2, c.3,
+            d.0, d.1, d.2, d.3,
+        )
+    }
+
     pub fn as_float_const_ptr(&self) -> *const GLfloat {
         unsafe { &(self.0).0 as *const GLfloat }
     }
@@ -6,9 +6,12 @@ use peppaint::{
     Actor,
     MyContext,
     ShaderProgram,
-    std::vertices::PosColor
+    std::vertices::PosColor,
+    Route,
 };
 
+use std::rc::Rc;
+
 impl Actor for ClosedTunnel {
     fn act(&mut self, ctx: &mut MyContext) {
         self.update(ctx.time() as f32);
@@ -20,18 +23,18 @@ pub struct ClosedTunnel {
     parts: Vec<ClosedTunnelPart>,
     dt: f32, 
     t_mid: f32, 
-    f: fn(f32) -> Vec3, 
+    f: Rc<dyn Route>, 
     radius: f32,
     p: Box<ShaderProgram>
 }
 
 impl ClosedTunnel {
 
-    pub fn ne_def(f: fn(f32) -> Vec3) -> Self {
+    pub fn ne_def(f: Rc<dyn Route>) -> Self {
         Self::threepiece(f, 2.0, 0.5)
     }
  
-    pub fn threepiece(f: fn(f32) -> Vec3, dt_part: f32, radius: f32) -> Self {
+    pub fn threepiece(f: Rc<dyn Route>, dt_part: f32, radius: f32) -> Self {
 
         let mut p = Box::new(peppaint::std::shaders::pos_color());
 
@@ -40,7 +43,7 @@ impl ClosedTunnel {
         for _ in 0..3 {
             let end = start + dt_part;
             let part = ClosedTunnelPart::from_function(
-                f, 
+                &*f, 
                 start, 
                 end, 
                 0.05, 
@@ -67,7 +70,7 @@ impl ClosedTunnel {
     pub fn update(&mut self, t: f32) {
         if t > self.t_mid {
             let new_part = ClosedTunnelPart::from_function(
-                self.f, 
+                &*self.f, 
                 self.parts[2].tend, self.parts[2].tend + self.dt, 
                 0.1, 
                 50, 
@@ -98,7 +101,7 @@ pub struct ClosedTunnelPart {
 
 impl ClosedTunnelPart {
     pub fn from_function(
-        f: fn(f32) -> Vec3,
+        f: &dyn Route,
         start: f32,
         end: f32,
         step: f32, 
@@ -126,8 +129,8 @@ impl ClosedTunnelPart {
         // create the first one and then loop
         let mut t = start;
         
-        let mut pos = f(t);
-        let mut vel = f(t + EPSILON) - pos;
+        let mut pos = Vec3::from_glm(&f.position(t));
+        let mut vel = Vec3::from_glm(&f.tangent(t));
 
         // get model matrix
         let mut model = Mat4::rotation_from_heading(&E3, &vel);
@@ -145,8 +148,8 @@ impl ClosedTunnelPart {
 
         while t < end {
             t += step;
-            pos = f(t);
-            vel = f(t + EPSILON) - pos;
+            pos = Vec3::from_glm(&f.position(t));
+            vel = Vec3::from_glm(&f.tangent(t));
 
             // get model matrix
             let mut model = Mat4::rotation_from_heading(&E3, &vel);
@@ -6,35 +6,46 @@ use peppaint::{
     vertex::*,
     constants::*,
     ShaderProgram,
+    ExportVertex,
+    Frustum,
+    CullStats,
+    Route,
+    Gui,
 };
 
+use std::rc::Rc;
+
 impl Actor for RingTunnel {
     fn start_acting(&mut self, ctx: &mut MyContext) {
         self.f = ctx.get_route();
     }
     fn act(&mut self, ctx: &mut MyContext) {
         self.update(ctx.time() as f32);
-        self.paint(&ctx.get_vp());
+        self.paint(&ctx.get_vp(), &mut ctx.cull_stats);
     }
 }
 
 #[allow(dead_code)]
 pub struct RingTunnel {
     tunnelpieces: Vec<RingTunnelPiece>, 
-    f: fn(f32) -> Vec3,
+    f: Rc<dyn Route>,
     final_t: f32, 
     step_t: f32,
+    // of the rings, the depth is how far they pulse
+    width: f32,
+    height: f32,
+    depth: f32,
     p: Box<ShaderProgram>
 }
 
 impl RingTunnel {
 
-    pub fn ne_def(f: fn(f32) -> Vec3) -> Self {
+    pub fn ne_def(f: Rc<dyn Route>) -> Self {
         Self::from_function(f, 0.2, 50, Vec3(2.0, 2.0, 0.5))
     }
 
     pub fn from_function(
-        f: fn(f32) -> Vec3, 
+        f: Rc<dyn Route>, 
         step_size: f32, 
         n: usize, 
         size: Vec3
@@ -49,8 +60,8 @@ impl RingTunnel {
 
         for i in 0..n {
             let t = step_size * (i as f32);
-            let pos = f(t);
-            let heading = f(t + EPSILON) - pos;
+            let pos = Vec3::from_glm(&f.position(t));
+            let heading = Vec3::from_glm(&f.tangent(t));
             let piece = RingTunnelPiece::new(
                 60,
                 0.1,
@@ -71,13 +82,29 @@ impl RingTunnel {
             f, 
             final_t, 
             step_t: step_size,
+            width: size.0,
+            height: size.1,
+            depth: 8.0,
             p
         }
     }
 
-    pub fn paint(&self, vp: &Mat4) {
+    pub fn tweak(&mut self, gui: &mut Gui) {
+        if gui.panel("Ring tunnel") {
+            gui.slider("width", &mut self.width, 0.1, 5.0);
+            gui.slider("height", &mut self.height, 0.1, 5.0);
+            gui.slider("depth", &mut self.depth, 0.0, 20.0);
+        }
+    }
+
+    // pieces behind the camera are skipped
+    pub fn paint(&self, vp: &Mat4, stats: &mut CullStats) {
+        let frustum = Frustum::from_mat4(vp);
         for piece in &self.tunnelpieces {
-            piece.paint(vp);
+            let bounds = piece.mesh.get_bounds();
+            if stats.test(&frustum, bounds.as_ref(), &piece.calc_model().to_glm()) {
+                piece.paint(vp);
+            }
         }
     }
 
@@ -85,12 +112,12 @@ impl RingTunnel {
         let len = self.tunnelpieces.len() as f32;
         for piece in &mut self.tunnelpieces {
             if piece.toc < (t - len / 2.0 * self.step_t) {
-                piece.position = (self.f)(self.final_t);
-                piece.heading = (self.f)(self.final_t + EPSILON);
+                piece.position = Vec3::from_glm(&self.f.position(self.final_t));
+                piece.heading = Vec3::from_glm(&self.f.tangent(self.final_t));
                 piece.toc = self.final_t;
                 self.final_t += self.step_t;
             }
-            piece.size.2 = 8.0 * t.sin();
+            piece.size = Vec3(self.width, self.height, self.depth * t.sin());
         }
     }
 }
@@ -204,7 +231,9 @@ impl Vertex for TunnelVertex {
             location: 0,
             n_elements: 3,
             type_: peppaint::Type::Float,
-            byte_offset: 0
+            byte_offset: 0,
+            normalized: false,
+            integer: false
         };
         let attrib_layouts = vec![al1];
         VertexLayout {
@@ -212,4 +241,8 @@ impl Vertex for TunnelVertex {
             attrib_layouts
         }
     }
+}
+
+impl ExportVertex for TunnelVertex {
+    fn position(&self) -> [f32; 3] { let p = self.pos; [p.0, p.1, p.2] }
 }
\ No newline at end of file
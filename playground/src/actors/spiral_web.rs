@@ -1,6 +1,7 @@
 use peppaint::{
     Actor,
-    MyContext
+    MyContext,
+    Gui,
 };
 
 pub struct SpiralWeb {
@@ -8,7 +9,8 @@ pub struct SpiralWeb {
     linedis: f32,
     linewidth: f32,
     linelenfac: f32,
-    n_lines: usize
+    n_lines: usize,
+    color: [f32; 3],
 }
 
 impl SpiralWeb {
@@ -21,7 +23,22 @@ impl SpiralWeb {
         Self { 
             timefac, linedis,
             n_lines, linewidth,
-            linelenfac
+            linelenfac,
+            color: [0.9, 0.8, 0.0],
+        }
+    }
+
+    pub fn tweak(&mut self, gui: &mut Gui) {
+        if gui.panel("Spiral web") {
+            gui.slider("time", &mut self.timefac, 0.0, 0.05);
+            gui.slider("distance", &mut self.linedis, 0.5, 10.0);
+            gui.slider("width", &mut self.linewidth, 0.5, 10.0);
+            gui.slider("length", &mut self.linelenfac, 0.5, 5.0);
+            gui.slider_usize("lines", &mut self.n_lines, 1, 1000);
+            gui.color("color", &mut self.color);
+            if gui.button("reset") {
+                *self = Self::ne_def();
+            }
         }
     }
 }
@@ -37,7 +54,7 @@ impl Actor for SpiralWeb {
         peppaint::gl_check_error();
         peppaint::gl_flush_error();
         
-        ctx.painter.color(0.9, 0.8, 0.0);
+        ctx.painter.color(self.color[0], self.color[1], self.color[2]);
 
         for i in 0..self.n_lines {
             let ia = i as f32;
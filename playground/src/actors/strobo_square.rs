@@ -2,9 +2,10 @@ use peppaint::{
     Float,
     PainterDepricated,
     Actor,
-    MyContext
+    MyContext,
+    Noise,
+    Gui,
 };
-use perlin_noise::PerlinNoise;
 
 pub struct StroboSquare {
     pub x: Float,
@@ -24,18 +25,23 @@ impl StroboSquare {
             b: 1.0
         }
     }
+    pub fn tweak(&mut self, gui: &mut Gui) {
+        if gui.panel("Strobo square") {
+            gui.slider("a", &mut self.a, 1.0, 2000.0);
+            gui.slider("b", &mut self.b, -1.0, 1.0);
+        }
+    }
+
     pub fn paint(&mut self, p: &mut PainterDepricated) {
         p.ident_projection();
         p.grey(1.0);
         p.square(self.x, self.y, self.w);
         p.paint();
     }
-    pub fn update(&mut self, noise: &PerlinNoise, mut time: f32) {
+    pub fn update(&mut self, noise: &Noise, mut time: f32) {
         time *= 100.0;
-        let n = noise.get((time/1379.0) as f64) as f32;
-        self.x = 0.5 * (n * 2.0 - 1.0);
-        let n = noise.get((time/1892.0) as f64) as f32;
-        self.y = 0.8 * (n * 2.0 - 1.0);
+        self.x = 0.5 * noise.perlin1(time/1379.0);
+        self.y = 0.8 * noise.perlin1(time/1892.0);
         self.w = (time/self.a).sin() + self.b;
 
         let mut pow = 0.98;
@@ -47,7 +53,7 @@ impl StroboSquare {
 
 impl Actor for StroboSquare {
     fn act(&mut self, ctx: &mut MyContext) {
-        self.update(&ctx.pnoise, ctx.real_time() as f32);
+        self.update(&ctx.noise, ctx.real_time() as f32);
         self.paint(&mut ctx.painter);
     }
 }
\ No newline at end of file
@@ -6,17 +6,22 @@ pub mod routes;
 pub use routes::*; 
 
 use peppaint::movie::movie::*;
+use peppaint::{ Playlist, Transition };
 
 use nalgebra_glm as glm;
 use glm::{ Mat4 };
 use std::path::Path;
 
+// N skips to the next movie, B goes back
 fn main() {
-    MoviePlayer::play_debug::<Test3>();
-    MoviePlayer::play_debug::<Test2>();
-    MoviePlayer::play_debug::<movielib::spiral_thing::SpiralThing>();
-    MoviePlayer::play_debug::<movielib::square_adjuster::SquareAdjuster>();
-    MoviePlayer::play_debug::<movielib::tunnel_simulator::TunnelSimulator>();
+    Playlist::new()
+        .add::<Test3>()
+        .add::<Test2>()
+        .add::<movielib::spiral_thing::SpiralThing>()
+        .add::<movielib::square_adjuster::SquareAdjuster>()
+        .add::<movielib::tunnel_simulator::TunnelSimulator>()
+        .transition(Transition::CrossFade(1.0))
+        .play_debug();
 }
 
 struct Test3 {
@@ -62,9 +67,9 @@ use peppaint::{
     MyContext,
     Actor,
     ShaderProgram,
-    Vec3,
     Vec4,
     std::vertices,
+    OrbitController,
 };
 
 use crate::{
@@ -73,13 +78,11 @@ use crate::{
 
 struct Test2 {
     program: ShaderProgram,
-    pos: Vec3,
     model: peppaint::Model,
-    camangles: (f32, f32)
 }
 
 impl Movie for Test2 {
-    fn setup(_ctx: &mut MyContext) -> Self {
+    fn setup(ctx: &mut MyContext) -> Self {
 
         let path = Path::new("/Users/pvdklei/Desktop/Code/graphics/playground/src/assets/rose/Models and Textures/rose.obj");
 
@@ -90,59 +93,22 @@ impl Movie for Test2 {
             "src/shaders/ringtunnel.vert"
         ).unwrap();
 
-        let pos = Vec3(0.0, 0.0, -5.0);
-        let camangles = (0.0, 0.0);
+        ctx.set_controller(OrbitController::new(glm::vec3(0.0, 0.0, 0.0), 5.0));
 
-        Self { program, pos, model, camangles }
+        Self { program, model }
     }
     fn update(&mut self, ctx: &mut MyContext) {
 
-        if ctx.is_key_pressed(Key::W) {
-            self.pos.2 += 0.5;
-        } 
-        if ctx.is_key_pressed(Key::S) {
-            self.pos.2 -= 0.5;
-        } 
-
-        if ctx.is_key_pressed(Key::A) {
-            self.pos.0 += 0.5;
-        } 
-        if ctx.is_key_pressed(Key::D) {
-            self.pos.0 -= 0.5;
-        } 
-
-        if ctx.is_key_pressed(Key::Up) {
-            self.pos.1 -= 0.4;
-        } 
-        if ctx.is_key_pressed(Key::Down) {
-            self.pos.1 += 0.4;
-        } 
-
-        if ctx.is_mouse_pressed() {
-            let (offx, offy) = ctx.get_cursor_direction();
-            let fac = 0.05;
-            self.camangles.0 += offy * fac;
-            self.camangles.1 += offx * fac;
-        } 
-
-        // self.pos.print();
-
         let scale = glm::scaling(&glm::vec3(0.1, 0.1, 0.1));
 
-        let trans = glm::translation(&glm::vec3(self.pos.0, self.pos.1, self.pos.2));
-        let pers = glm::perspective(1.0, glm::half_pi(), 0.5, 50.0);
-        let rotx = glm::rotation(self.camangles.0, &glm::vec3(1.0, 0.0, 0.0));
-        let roty = glm::rotation(self.camangles.1, &glm::vec3(0.0, 1.0, 0.0));
-
         let model = scale;
-        let view = pers * rotx * roty * trans;
-        let mvp = view * model;
+        let mvp = ctx.get_vp_glm() * model;
 
         self.program.bind();
         self.program.set_mat4f("uMVP", mvp.as_ptr());
         self.program.set_vec4f_depricated("uColor", &Vec4(1.0, 1.0 , 1.0, 1.0));
 
-        self.model.show();
+        self.model.show(&mut self.program);
 
         peppaint::gl_check_error();
         peppaint::gl_flush_error();
@@ -241,13 +207,17 @@ impl Vertex for TexVertex {
             location: 0,
             n_elements: 2, 
             byte_offset: 0,
-            type_: peppaint::Type::Float
+            type_: peppaint::Type::Float,
+            normalized: false,
+            integer: false
         };
         let al2 = AttributeLayout {
             location: 1,
             n_elements: 2,
             byte_offset: 8,
-            type_: peppaint::Type::Float
+            type_: peppaint::Type::Float,
+            normalized: false,
+            integer: false
         };
         let als = vec![al1, al2];
         VertexLayout {
@@ -27,5 +27,7 @@ impl Movie for SpiralThing {
         use peppaint::Actor;
         self.spiral.act(ctx);
         self.square.act(ctx);
+        self.spiral.tweak(&mut ctx.gui);
+        self.square.tweak(&mut ctx.gui);
     }
 }
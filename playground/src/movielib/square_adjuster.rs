@@ -18,6 +18,7 @@ impl Movie for SquareAdjuster {
     fn show(&mut self, ctx: &mut MyContext) {
         use peppaint::Actor;
         self.square.act(ctx);
+        self.square.tweak(&mut ctx.gui);
     }
 
 }
\ No newline at end of file
@@ -2,8 +2,12 @@ use peppaint::{
     MyContext,
     Movie,
     Actor,
+    Light,
+    Banking,
 };
 
+use nalgebra_glm as glm;
+
 use crate::{
     RingTunnel,
     FirstPersonRollerCoasterCam,
@@ -24,12 +28,13 @@ impl Movie for TunnelSimulator {
 
         let camera = crate::FirstPersonRollerCoasterCam::ne();
 
-        let f = f2;
-
-        ctx.set_route(f);
+        ctx.set_route(route2());
+        ctx.set_banking(Some(Banking::default()));
+        let route = ctx.get_route();
+        ctx.add_light(Light::headlight(glm::vec3(1.0, 0.95, 0.8), 0.5, 15.0));
 
-        let pooled_tunnel = RingTunnel::ne_def(f);
-        let closed_tunnel = ClosedTunnel::ne_def(f);
+        let pooled_tunnel = RingTunnel::ne_def(route.clone());
+        let closed_tunnel = ClosedTunnel::ne_def(route);
 
         Self { 
             pooled_tunnel,
@@ -43,5 +48,9 @@ impl Movie for TunnelSimulator {
         // self.closed_tunnel.act(ctx);
         self.pooled_tunnel.act(ctx);
     }
+
+    fn show(&mut self, ctx: &mut MyContext) {
+        self.pooled_tunnel.tweak(&mut ctx.gui);
+    }
 }
 